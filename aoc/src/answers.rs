@@ -0,0 +1,138 @@
+use crate::{Error, ParseError, Part};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Identifies an input by its contents (64-bit FNV-1a), so answers follow the input rather than
+/// its path.
+pub fn checksum(input: &str) -> u64 {
+    input.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+        (hash ^ b as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// The known-good answers for one input.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Recorded {
+    pub part_one: String,
+    pub part_two: String,
+}
+
+impl Recorded {
+    pub fn get(&self, part: Part) -> &str {
+        match part {
+            Part::One => &self.part_one,
+            Part::Two => &self.part_two,
+        }
+    }
+
+    pub fn set(&mut self, part: Part, answer: String) {
+        match part {
+            Part::One => self.part_one = answer,
+            Part::Two => self.part_two = answer,
+        }
+    }
+}
+
+/// A day's answers file: one tab separated `checksum part-one part-two` line per known input.
+/// Lines starting with `#` are comments.
+#[derive(Default)]
+pub struct Answers(BTreeMap<u64, Recorded>);
+
+impl Answers {
+    pub fn parse(text: &str) -> Result<Self, Error> {
+        let mut answers = BTreeMap::new();
+        let mut errors = Vec::new();
+
+        for (i, line) in text.lines().enumerate() {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            match Self::parse_line(line) {
+                Ok((checksum, recorded)) => {
+                    answers.insert(checksum, recorded);
+                }
+                Err(e) => errors.push(e.at_line(i + 1)),
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(Error::Parse(errors));
+        }
+        Ok(Self(answers))
+    }
+
+    fn parse_line(line: &str) -> Result<(u64, Recorded), ParseError> {
+        let mut fields = line.split('\t');
+        let checksum = fields.next().unwrap_or_default();
+        let checksum = u64::from_str_radix(checksum, 16)
+            .map_err(|e| ParseError::new(line, 1, format!("invalid checksum: {}", e)))?;
+
+        let mut answer = || {
+            fields
+                .next()
+                .map(String::from)
+                .ok_or_else(|| ParseError::new(line, line.len() + 1, "missing answer"))
+        };
+        let recorded = Recorded {
+            part_one: answer()?,
+            part_two: answer()?,
+        };
+        Ok((checksum, recorded))
+    }
+
+    pub fn get(&self, checksum: u64) -> Option<&Recorded> {
+        self.0.get(&checksum)
+    }
+
+    /// Records a single part's answer, keeping whatever was known about the other part.
+    pub fn record(&mut self, checksum: u64, part: Part, answer: String) {
+        self.0
+            .entry(checksum)
+            .or_insert_with(|| Recorded {
+                part_one: String::new(),
+                part_two: String::new(),
+            })
+            .set(part, answer);
+    }
+}
+
+impl fmt::Display for Answers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# checksum\tpart one\tpart two")?;
+        for (checksum, recorded) in self.0.iter() {
+            writeln!(
+                f,
+                "{:016x}\t{}\t{}",
+                checksum, recorded.part_one, recorded.part_two
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let mut answers = Answers::default();
+        answers.record(checksum("1721\n979\n"), Part::One, String::from("514579"));
+        answers.record(
+            checksum("1721\n979\n"),
+            Part::Two,
+            String::from("no answer"),
+        );
+
+        let parsed = Answers::parse(&answers.to_string()).unwrap();
+        let recorded = parsed.get(checksum("1721\n979\n")).unwrap();
+        assert_eq!("514579", recorded.get(Part::One));
+        assert_eq!("no answer", recorded.get(Part::Two));
+    }
+
+    #[test]
+    fn test_malformed() {
+        assert!(Answers::parse("# comment\nnot-hex\t1\t2\n").is_err());
+        assert!(Answers::parse("00ff\t1\n").is_err());
+    }
+}
@@ -0,0 +1,140 @@
+use std::{error, fmt, io};
+
+/// A single malformed piece of puzzle input.
+///
+/// `line` and `column` are both 1-based. `column` counts bytes into `text`, which holds the whole
+/// offending line so the problem can be shown in context.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseError {
+    pub file: Option<String>,
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new(text: &str, column: usize, message: impl Into<String>) -> Self {
+        Self {
+            file: None,
+            line: 1,
+            column,
+            text: String::from(text),
+            message: message.into(),
+        }
+    }
+
+    /// Builds an error pointing at `part`, which must be a slice of `text`.
+    pub fn at(text: &str, part: &str, message: impl Into<String>) -> Self {
+        let offset = (part.as_ptr() as usize).wrapping_sub(text.as_ptr() as usize);
+        let column = if offset <= text.len() { offset + 1 } else { 1 };
+        Self::new(text, column, message)
+    }
+
+    pub fn at_line(mut self, line: usize) -> Self {
+        self.line = line;
+        self
+    }
+
+    pub fn in_file(mut self, file: &str) -> Self {
+        self.file = Some(String::from(file));
+        self
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{}:", file)?;
+        }
+        write!(
+            f,
+            "{}:{}: {}: '{}'",
+            self.line, self.column, self.message, self.text
+        )
+    }
+}
+
+impl error::Error for ParseError {}
+
+/// Everything that can go wrong while loading and solving a day.
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    /// Every malformed line found in the input, not just the first.
+    Parse(Vec<ParseError>),
+    /// The input was fine but couldn't be solved as asked (an answer overflowed, a search would
+    /// be too big to run, ...).
+    Solve(String),
+}
+
+impl Error {
+    /// Tags every parse error with the file it came from.
+    pub fn in_file(self, file: &str) -> Self {
+        match self {
+            Error::Parse(errors) => {
+                Error::Parse(errors.into_iter().map(|e| e.in_file(file)).collect())
+            }
+            e => e,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "{}", e),
+            Error::Solve(message) => write!(f, "{}", message),
+            Error::Parse(errors) => {
+                for (i, e) in errors.iter().enumerate() {
+                    if i > 0 {
+                        writeln!(f)?;
+                    }
+                    write!(f, "{}", e)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(e: ParseError) -> Self {
+        Error::Parse(vec![e])
+    }
+}
+
+impl From<Vec<ParseError>> for Error {
+    fn from(errors: Vec<ParseError>) -> Self {
+        Error::Parse(errors)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_at() {
+        let line = "byr:1937 iyr2017";
+        let e = ParseError::at(line, &line[9..], "oops");
+        assert_eq!(10, e.column);
+        assert_eq!(1, ParseError::at(line, "elsewhere", "oops").column);
+    }
+
+    #[test]
+    fn test_display() {
+        let e = ParseError::new("bad line", 4, "oops")
+            .at_line(3)
+            .in_file("input.txt");
+        assert_eq!("input.txt:3:4: oops: 'bad line'", e.to_string());
+    }
+}
@@ -0,0 +1,224 @@
+use crate::{Error, Format, Solution};
+use std::fmt::Display;
+use std::io::{BufRead, BufReader, Read};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::{env, fs, io};
+
+/// Where a puzzle input comes from.
+pub enum Source {
+    Stdin,
+    File(PathBuf),
+    /// An input compiled into the binary by the `embed-inputs` feature.
+    Embedded(&'static str),
+}
+
+impl Source {
+    /// `-` means stdin, anything else is a path.
+    pub fn from_arg(arg: &str) -> Self {
+        match arg {
+            "-" => Source::Stdin,
+            path => Source::File(PathBuf::from(path)),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            Source::Stdin => String::from("<stdin>"),
+            Source::File(path) => path.to_string_lossy().into_owned(),
+            Source::Embedded(_) => String::from("<embedded>"),
+        }
+    }
+
+    pub fn read(&self) -> io::Result<String> {
+        match self {
+            Source::Stdin => {
+                let mut buf = String::new();
+                io::stdin().read_to_string(&mut buf)?;
+                Ok(buf)
+            }
+            Source::File(path) => fs::read_to_string(path),
+            Source::Embedded(input) => Ok(String::from(*input)),
+        }
+    }
+
+    /// Opens the input to be read a bit at a time, for days that don't need all of it at once.
+    pub fn reader(&self) -> io::Result<Box<dyn BufRead>> {
+        Ok(match self {
+            Source::Stdin => Box::new(io::stdin().lock()),
+            Source::File(path) => Box::new(BufReader::new(fs::File::open(path)?)),
+            Source::Embedded(input) => Box::new(input.as_bytes()),
+        })
+    }
+
+    /// Reads and parses the input, tagging any parse errors with where they came from.
+    pub fn parse<S: Solution>(&self) -> Result<S, Error> {
+        S::parse(&self.read()?).map_err(|e| e.in_file(&self.name()))
+    }
+}
+
+/// A day's command line arguments. Days pull their own flags out first, and whatever's left
+/// names the inputs.
+pub struct Args(Vec<String>);
+
+impl Args {
+    pub fn from_env() -> Self {
+        Args(env::args().skip(1).collect())
+    }
+
+    /// Removes a switch like `--all`, returning whether it was given.
+    pub fn flag(&mut self, name: &str) -> bool {
+        let given = self.0.iter().any(|a| a == name);
+        self.0.retain(|a| a != name);
+        given
+    }
+
+    /// Removes every `name <value>` pair, parsing the last value given.
+    pub fn value<T>(&mut self, name: &str) -> Result<Option<T>, Error>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let mut value = None;
+        while let Some(i) = self.0.iter().position(|a| a == name) {
+            if i + 1 == self.0.len() {
+                return Err(invalid(format!("missing value for '{}'", name)));
+            }
+            let raw = self.0.remove(i + 1);
+            self.0.remove(i);
+            let parsed = raw
+                .parse()
+                .map_err(|e| invalid(format!("invalid value for '{}': '{}': {}", name, raw, e)))?;
+            value = Some(parsed);
+        }
+        Ok(value)
+    }
+
+    /// Fails if `--format json` was given along with any of the day's own flags that were used,
+    /// as `(name, used)` pairs. JSON output only has the puzzle's answers, so those flags would
+    /// otherwise be silently ignored. `--format` itself is left for `for_each_input_in`.
+    pub fn reject_with_json(&self, flags: &[(&str, bool)]) -> Result<(), Error> {
+        let json =
+            crate::parse_format(self.0.iter().cloned()).is_ok_and(|(f, _)| f == Format::Json);
+        Self::reject_with(
+            ("--format json", json),
+            "only reports the puzzle's answers",
+            flags,
+        )
+    }
+
+    /// Fails if `flag` (as a `(name, used)` pair) was used along with any of `flags` that were,
+    /// because `why` means they'd otherwise be silently ignored.
+    pub fn reject_with(flag: (&str, bool), why: &str, flags: &[(&str, bool)]) -> Result<(), Error> {
+        let used: Vec<&str> = flags
+            .iter()
+            .filter(|(_, used)| *used)
+            .map(|(name, _)| *name)
+            .collect();
+        if flag.1 && !used.is_empty() {
+            return Err(invalid(format!(
+                "{} {}, so it can't be used with {}",
+                flag.0,
+                why,
+                used.join(", ")
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl<S: Into<String>> std::iter::FromIterator<S> for Args {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        Args(iter.into_iter().map(Into::into).collect())
+    }
+}
+
+impl IntoIterator for Args {
+    type Item = String;
+    type IntoIter = std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+fn invalid(message: String) -> Error {
+    Error::Io(io::Error::new(io::ErrorKind::InvalidInput, message))
+}
+
+/// Turns command line arguments into sources, falling back to the embedded input when there
+/// aren't any.
+pub fn sources(
+    args: impl IntoIterator<Item = String>,
+    embedded: Option<&'static str>,
+) -> Result<Vec<Source>, Error> {
+    let sources: Vec<Source> = args.into_iter().map(|a| Source::from_arg(&a)).collect();
+    if !sources.is_empty() {
+        return Ok(sources);
+    }
+
+    embedded
+        .map(|input| vec![Source::Embedded(input)])
+        .ok_or_else(|| {
+            Error::Io(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "missing filename arg (use '-' for stdin)",
+            ))
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_args() {
+        let mut args: Args = vec!["--all", "a.txt", "--k", "3", "-", "--k", "4"]
+            .into_iter()
+            .collect();
+
+        assert!(args.flag("--all"));
+        assert!(!args.flag("--all"));
+        assert_eq!(Some(4), args.value::<usize>("--k").unwrap());
+        assert_eq!(None, args.value::<usize>("--target").unwrap());
+        assert_eq!(vec!["a.txt", "-"], args.into_iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_reject_with_json() {
+        let args: Args = vec!["a.txt", "--format", "json"].into_iter().collect();
+        assert!(args.reject_with_json(&[("--k", false)]).is_ok());
+        let e = args
+            .reject_with_json(&[("--k", true), ("--all", false), ("--target", true)])
+            .unwrap_err();
+        assert!(e.to_string().ends_with("can't be used with --k, --target"));
+
+        let args: Args = vec!["--format", "json", "--format", "text"]
+            .into_iter()
+            .collect();
+        assert!(args.reject_with_json(&[("--k", true)]).is_ok());
+        // leaves `--format` for `for_each_input_in`
+        assert_eq!(4, args.into_iter().count());
+    }
+
+    #[test]
+    fn test_reject_with() {
+        let why = "only looks for pairs";
+        assert!(Args::reject_with(("--stream", false), why, &[("--subset", true)]).is_ok());
+        assert!(Args::reject_with(("--stream", true), why, &[("--subset", false)]).is_ok());
+        let e = Args::reject_with(("--stream", true), why, &[("--subset", true)]).unwrap_err();
+        assert_eq!(
+            "--stream only looks for pairs, so it can't be used with --subset",
+            e.to_string()
+        );
+    }
+
+    #[test]
+    fn test_args_invalid() {
+        let mut args: Args = vec!["--k", "four"].into_iter().collect();
+        assert!(args.value::<usize>("--k").is_err());
+
+        let mut args: Args = vec!["a.txt", "--k"].into_iter().collect();
+        assert!(args.value::<usize>("--k").is_err());
+    }
+}
@@ -0,0 +1,436 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::time::{Duration, Instant};
+use std::{fmt, io, process};
+
+pub use answers::Answers;
+pub use error::{Error, ParseError};
+pub use input::{Args, Source};
+
+pub mod answers;
+mod error;
+pub mod input;
+
+// re-exported so days can build their `details`, generators and answer types without depending
+// on them themselves
+pub use rand;
+pub use serde;
+pub use serde_json;
+
+/// A single day's puzzle.
+///
+/// Implementors are built once from the raw puzzle input and then asked for each part's answer.
+/// A part returns `None` when the input simply doesn't contain an answer (e.g. no two expenses
+/// sum to 2020), which is different from the input being malformed, and `Error::Solve` when the
+/// input can't be solved as asked (an answer overflows, the bags contain each other, ...).
+pub trait Solution: Sized {
+    type PartOne: fmt::Display + Serialize;
+    type PartTwo: fmt::Display + Serialize;
+
+    fn parse(input: &str) -> Result<Self, Error>;
+
+    fn part_one(&self) -> Result<Option<Self::PartOne>, Error>;
+
+    fn part_two(&self) -> Result<Option<Self::PartTwo>, Error>;
+
+    /// Intermediate results worth reporting alongside the answers in JSON output (per-slope tree
+    /// counts, per-passport status, ...).
+    fn details(&self) -> Value {
+        Value::Null
+    }
+}
+
+/// A random puzzle input along with the answers a correct solver must give for it.
+pub struct Generated<S: Solution> {
+    pub input: String,
+    pub part_one: Option<S::PartOne>,
+    pub part_two: Option<S::PartTwo>,
+}
+
+/// Days that can build random inputs with known answers, to stress and check their solvers.
+///
+/// The answers must come from how the input was built, not from running the solver on it,
+/// otherwise they're no use as an oracle.
+pub trait Generate: Solution {
+    /// Builds an input with roughly `size` records (lines, passports, groups, ...).
+    fn generate<R: Rng>(rng: &mut R, size: usize) -> Generated<Self>;
+}
+
+/// Generates an input from a seed, returning it with its expected answers as JSON.
+pub fn generate<S: Generate>(seed: u64, size: usize) -> (String, Value) {
+    let generated = S::generate(&mut StdRng::seed_from_u64(seed), size);
+    let answers = json!({
+        Part::One.key(): generated.part_one.map(|a| json!(a)),
+        Part::Two.key(): generated.part_two.map(|a| json!(a)),
+    });
+    (generated.input, answers)
+}
+
+/// How results are printed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    /// Each day's own human readable output.
+    Text,
+    /// One JSON document per line for every input.
+    Json,
+}
+
+impl std::str::FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            _ => Err(format!(
+                "unknown format: '{}' (expected 'text' or 'json')",
+                s
+            )),
+        }
+    }
+}
+
+/// Pulls `--format <text|json>` out of the command line arguments, returning it with whatever's
+/// left.
+fn parse_format(args: impl Iterator<Item = String>) -> Result<(Format, Vec<String>), Error> {
+    let mut format = Format::Text;
+    let mut rest = Vec::new();
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        if arg != "--format" {
+            rest.push(arg);
+            continue;
+        }
+        format = args
+            .next()
+            .ok_or_else(|| String::from("missing value for '--format'"))
+            .and_then(|f| f.parse())
+            .map_err(|e| Error::Io(io::Error::new(io::ErrorKind::InvalidInput, e)))?;
+    }
+    Ok((format, rest))
+}
+
+/// Parses every input named on the command line in turn (or the embedded one when there aren't
+/// any) and hands each to `f`, stopping at the first that fails (or that `f` fails on). A header
+/// naming the input is printed before each one when there's more than one.
+///
+/// With `--format json`, `f` isn't called: each input's report is printed as JSON instead.
+pub fn for_each_input<S: Solution>(
+    embedded: Option<&'static str>,
+    f: impl FnMut(S) -> Result<(), Error>,
+) -> Result<(), Error> {
+    for_each_input_in(Args::from_env(), embedded, f)
+}
+
+/// Like `for_each_input`, for days that take flags of their own: `args` is what's left once
+/// they've been pulled out.
+pub fn for_each_input_in<S: Solution>(
+    args: Args,
+    embedded: Option<&'static str>,
+    f: impl FnMut(S) -> Result<(), Error>,
+) -> Result<(), Error> {
+    for_each_input_with(args, embedded, S::parse, f)
+}
+
+/// Like `for_each_input_in`, for days whose flags change how the input is parsed: every input
+/// goes through `parse` rather than `S::parse`, JSON reports included.
+pub fn for_each_input_with<S: Solution>(
+    args: Args,
+    embedded: Option<&'static str>,
+    parse: impl Fn(&str) -> Result<S, Error>,
+    mut f: impl FnMut(S) -> Result<(), Error>,
+) -> Result<(), Error> {
+    let (format, args) = parse_format(args.into_iter())?;
+    let sources = input::sources(args, embedded)?;
+
+    if format == Format::Json {
+        for source in sources.iter() {
+            let input = source.read()?;
+            let report = solve_with(&input, &[Part::One, Part::Two], &parse)
+                .map_err(|e| e.in_file(&source.name()))?;
+
+            let mut doc = report.to_json();
+            doc["input"] = json!(source.name());
+            println!("{}", doc);
+        }
+        return Ok(());
+    }
+
+    for (i, source) in sources.iter().enumerate() {
+        if sources.len() > 1 {
+            if i > 0 {
+                println!();
+            }
+            println!("==> {} <==", source.name());
+        }
+        f(parse(&source.read()?).map_err(|e| e.in_file(&source.name()))?)?;
+    }
+    Ok(())
+}
+
+/// Parses each line with `f`, collecting every malformed line rather than stopping at the first.
+pub fn parse_lines<T>(
+    input: &str,
+    mut f: impl FnMut(&str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, Error> {
+    let mut parsed = Vec::new();
+    let mut errors = Vec::new();
+
+    for (i, line) in input.lines().enumerate() {
+        match f(line) {
+            Ok(t) => parsed.push(t),
+            Err(e) => errors.push(e.at_line(i + 1)),
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(Error::Parse(errors));
+    }
+    Ok(parsed)
+}
+
+/// Runs a day's `main`, printing the error and exiting non-zero if it fails.
+pub fn main(run: impl FnOnce() -> Result<(), Error>) {
+    if let Err(e) = run() {
+        eprintln!("{}", e);
+        process::exit(1);
+    }
+}
+
+/// Treats output that stopped being read partway (`| head`, say) as written rather than failed.
+pub fn ignore_broken_pipe(written: io::Result<()>) -> Result<(), Error> {
+    match written {
+        // whatever's reading has seen enough
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+        result => Ok(result?),
+    }
+}
+
+/// Formats an answer for printing, falling back to a placeholder when there isn't one.
+pub fn answer<T: fmt::Display>(answer: Option<T>) -> String {
+    answer.map_or_else(|| String::from("no answer"), |a| a.to_string())
+}
+
+/// One of the two parts of a day's puzzle.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    /// The name used for this part in JSON output.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Part::One => "part_one",
+            Part::Two => "part_two",
+        }
+    }
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Part::One => write!(f, "1"),
+            Part::Two => write!(f, "2"),
+        }
+    }
+}
+
+/// A single step of a run and how long it took.
+pub struct Timed<T> {
+    pub value: T,
+    pub elapsed: Duration,
+}
+
+impl<T> Timed<T> {
+    pub fn measure(f: impl FnOnce() -> T) -> Self {
+        let start = Instant::now();
+        let value = f();
+        Self {
+            value,
+            elapsed: start.elapsed(),
+        }
+    }
+}
+
+/// The outcome of running a day against one input: the parse plus every part that was asked for.
+pub struct Report {
+    pub parse: Duration,
+    pub parts: Vec<(Part, Timed<Option<Value>>)>,
+    pub details: Value,
+}
+
+impl Report {
+    /// The part's answer as it should be printed, or `None` if that part wasn't run.
+    pub fn answer(&self, part: Part) -> Option<String> {
+        self.parts
+            .iter()
+            .find(|(p, _)| *p == part)
+            .map(|(_, timed)| match &timed.value {
+                Some(Value::String(s)) => s.clone(),
+                a => answer(a.as_ref()),
+            })
+    }
+
+    /// A stable, structured version of the report. Keys are always sorted, parts that weren't
+    /// run are left out, and a part without an answer is `null`.
+    pub fn to_json(&self) -> Value {
+        let mut answers = json!({});
+        let mut timings = json!({ "parse": self.parse.as_nanos() as u64 });
+        for (part, answer) in self.parts.iter() {
+            answers[part.key()] = answer.value.clone().unwrap_or(Value::Null);
+            timings[part.key()] = json!(answer.elapsed.as_nanos() as u64);
+        }
+
+        json!({
+            "answers": answers,
+            "timings_ns": timings,
+            "details": self.details,
+        })
+    }
+}
+
+/// Parses the input and solves the requested parts, timing each step separately.
+pub fn solve<S: Solution>(input: &str, parts: &[Part]) -> Result<Report, Error> {
+    solve_with(input, parts, S::parse)
+}
+
+/// Like `solve`, parsing the input with `parse` rather than `S::parse`.
+pub fn solve_with<S: Solution>(
+    input: &str,
+    parts: &[Part],
+    parse: impl FnOnce(&str) -> Result<S, Error>,
+) -> Result<Report, Error> {
+    let solution = Timed::measure(|| parse(input));
+    let solution = Timed {
+        value: solution.value?,
+        elapsed: solution.elapsed,
+    };
+
+    let parts = parts
+        .iter()
+        .map(|&part| {
+            let answer = Timed::measure(|| match part {
+                Part::One => solution.value.part_one().map(|a| a.map(|a| json!(a))),
+                Part::Two => solution.value.part_two().map(|a| a.map(|a| json!(a))),
+            });
+            let answer = Timed {
+                value: answer.value?,
+                elapsed: answer.elapsed,
+            };
+            Ok((part, answer))
+        })
+        .collect::<Result<_, Error>>()?;
+
+    Ok(Report {
+        parse: solution.elapsed,
+        parts,
+        details: solution.value.details(),
+    })
+}
+
+/// Declares the day's `INPUT`: its committed `input.txt` when built with the `embed-inputs`
+/// feature, `None` otherwise.
+#[macro_export]
+macro_rules! embed_input {
+    () => {
+        #[cfg(feature = "embed-inputs")]
+        pub const INPUT: Option<&str> = Some(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/input.txt"
+        )));
+        #[cfg(not(feature = "embed-inputs"))]
+        pub const INPUT: Option<&str> = None;
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct Sample(Vec<u32>);
+
+    impl Solution for Sample {
+        type PartOne = u32;
+        type PartTwo = u32;
+
+        fn parse(input: &str) -> Result<Self, Error> {
+            Ok(Sample(parse_lines(input, |l| {
+                l.parse().map_err(|_| ParseError::new(l, 1, "not a number"))
+            })?))
+        }
+
+        fn part_one(&self) -> Result<Option<u32>, Error> {
+            self.0
+                .iter()
+                .try_fold(0u32, |sum, &n| sum.checked_add(n))
+                .map(Some)
+                .ok_or_else(|| Error::Solve(String::from("sum overflows u32")))
+        }
+
+        fn part_two(&self) -> Result<Option<u32>, Error> {
+            Ok(None)
+        }
+    }
+
+    #[test]
+    fn test_to_json() {
+        let doc = solve::<Sample>("1\n2\n3", &[Part::One, Part::Two])
+            .unwrap()
+            .to_json();
+
+        assert_eq!(json!(6), doc["answers"]["part_one"]);
+        assert_eq!(Value::Null, doc["answers"]["part_two"]);
+        assert!(doc["timings_ns"]["parse"].is_u64());
+    }
+
+    #[test]
+    fn test_solve_error() {
+        let e = solve::<Sample>("4294967295\n1", &[Part::One]);
+        assert!(matches!(e, Err(Error::Solve(_))));
+        // a part that wasn't asked for can't fail
+        assert!(solve::<Sample>("4294967295\n1", &[Part::Two]).is_ok());
+    }
+
+    #[test]
+    fn test_solve_with() {
+        // a parse of the day's own that skips what `Sample::parse` would fail on
+        let lenient = |input: &str| {
+            Ok(Sample(
+                input.lines().filter_map(|l| l.parse().ok()).collect(),
+            ))
+        };
+        assert!(solve::<Sample>("1\nx\n2", &[Part::One]).is_err());
+        let doc = solve_with("1\nx\n2", &[Part::One], lenient)
+            .unwrap()
+            .to_json();
+        assert_eq!(json!(3), doc["answers"]["part_one"]);
+    }
+
+    #[test]
+    fn test_ignore_broken_pipe() {
+        let failed = |kind| Err(io::Error::new(kind, "failed"));
+        assert!(ignore_broken_pipe(Ok(())).is_ok());
+        assert!(ignore_broken_pipe(failed(io::ErrorKind::BrokenPipe)).is_ok());
+        assert!(matches!(
+            ignore_broken_pipe(failed(io::ErrorKind::PermissionDenied)),
+            Err(Error::Io(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_format() {
+        let args = vec!["a.txt", "--format", "json", "-"]
+            .into_iter()
+            .map(String::from);
+        let (format, rest) = parse_format(args).unwrap();
+        assert_eq!(Format::Json, format);
+        assert_eq!(vec!["a.txt", "-"], rest);
+
+        assert!(parse_format(vec![String::from("--format")].into_iter()).is_err());
+    }
+}
@@ -0,0 +1,74 @@
+use aoc::serde::Serialize;
+use std::convert::{TryFrom, TryInto};
+use std::error;
+use std::fmt;
+use std::hash::Hash;
+use std::num::ParseIntError;
+use std::str::FromStr;
+
+/// An integer type expense entries can be read as.
+pub trait Entry:
+    Copy
+    + Ord
+    + Hash
+    + fmt::Debug
+    + fmt::Display
+    + FromStr<Err = ParseIntError>
+    + TryFrom<u16>
+    + TryInto<i128>
+    + Serialize
+    + 'static
+{
+    /// The type's name, for messages.
+    const NAME: &'static str;
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+}
+
+macro_rules! entry {
+    ($($t:ty),*) => {
+        $(
+            impl Entry for $t {
+                const NAME: &'static str = stringify!($t);
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_add(self, rhs)
+                }
+
+                fn checked_sub(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_sub(self, rhs)
+                }
+
+                fn checked_mul(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_mul(self, rhs)
+                }
+            }
+        )*
+    };
+}
+
+entry!(i8, i16, i32, i64, i128, u8, u16, u32, u64, u128);
+
+/// A sum or product of entries that doesn't fit in their integer type.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Overflow {
+    Sum(&'static str),
+    Product(&'static str),
+}
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Overflow::Sum(ty) => write!(f, "sum of entries overflows {}", ty),
+            Overflow::Product(ty) => write!(f, "product of entries overflows {}", ty),
+        }
+    }
+}
+
+impl error::Error for Overflow {}
@@ -0,0 +1,80 @@
+use crate::ExpenseReport;
+use aoc::rand::seq::SliceRandom;
+use aoc::rand::Rng;
+use aoc::{Generate, Generated};
+
+/// Every way of picking `k` entries (repeats allowed, like the solver) that sums to 2020.
+fn products(planted: &[u16], k: usize) -> Vec<u64> {
+    fn go(planted: &[u16], k: usize, start: usize, sum: u32, product: u64, out: &mut Vec<u64>) {
+        if k == 0 {
+            if sum == 2020 {
+                out.push(product);
+            }
+            return;
+        }
+        for i in start..planted.len() {
+            let n = planted[i];
+            go(planted, k - 1, i, sum + n as u32, product * n as u64, out);
+        }
+    }
+
+    let mut out = Vec::new();
+    go(planted, k, 0, 0, 1, &mut out);
+    out
+}
+
+impl Generate for ExpenseReport {
+    /// Plants one pair and one triple summing to 2020 among entries that are all bigger than 2020,
+    /// so nothing but the planted entries can ever be part of an answer.
+    fn generate<R: Rng>(rng: &mut R, size: usize) -> Generated<Self> {
+        let planted = loop {
+            let a = rng.gen_range(1..2020);
+            let c = rng.gen_range(1..2019);
+            let d = rng.gen_range(1..2020 - c);
+            let planted = [a, 2020 - a, c, d, 2020 - c - d];
+
+            // the planted numbers can combine in ways we didn't intend (1010 pairs with itself,
+            // a + b + x = 2020 for small x, ...), which is only a problem if it changes the answer
+            let pairs = products(&planted, 2);
+            let triples = products(&planted, 3);
+            if pairs.iter().all(|p| *p == pairs[0]) && triples.iter().all(|p| *p == triples[0]) {
+                break planted;
+            }
+        };
+
+        let mut numbers: Vec<u16> = (planted.len()..size.max(planted.len()))
+            .map(|_| rng.gen_range(2021..20000))
+            .collect();
+        numbers.extend_from_slice(&planted);
+        numbers.shuffle(rng);
+
+        let input = numbers
+            .iter()
+            .map(|n| format!("{}\n", n))
+            .collect::<String>();
+
+        Generated {
+            input,
+            part_one: Some(planted[0] as i64 * planted[1] as i64),
+            part_two: Some(planted[2] as i64 * planted[3] as i64 * planted[4] as i64),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aoc::rand::rngs::StdRng;
+    use aoc::rand::SeedableRng;
+    use aoc::Solution;
+
+    #[test]
+    fn test_generated_answers() {
+        for seed in 0..20 {
+            let generated = ExpenseReport::generate(&mut StdRng::seed_from_u64(seed), 100);
+            let report = ExpenseReport::parse(&generated.input).unwrap();
+            assert_eq!(generated.part_one, report.part_one().unwrap());
+            assert_eq!(generated.part_two, report.part_two().unwrap());
+        }
+    }
+}
@@ -0,0 +1,789 @@
+use aoc::serde_json::{json, Value};
+use aoc::{Error, ParseError, Solution};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+pub use entry::{Entry, Overflow};
+pub use nearest::{nearest_k, Nearest};
+pub use stream::Pairs;
+pub use subset::{subset_sum, SubsetError, MAX_CELLS};
+
+aoc::embed_input!();
+
+mod entry;
+mod generate;
+mod nearest;
+mod stream;
+mod subset;
+
+/// The expense report: one number per line, read as any integer type (`i64` unless asked
+/// otherwise).
+pub struct ExpenseReport<T = i64> {
+    pub numbers: Vec<T>,
+}
+
+/// The total the puzzle is after.
+pub const TARGET: u16 = 2020;
+
+/// Entries that add up to the target, along with their product.
+#[derive(Debug, PartialEq)]
+pub struct Combination<T> {
+    pub entries: Vec<T>,
+    /// The line each entry is on, counting from 1.
+    pub lines: Vec<usize>,
+    pub product: T,
+}
+
+impl<T: Entry> Combination<T> {
+    pub(crate) fn new(entries: Vec<T>, lines: Vec<usize>) -> Result<Self, Overflow> {
+        let product = entries
+            .iter()
+            .try_fold(T::ONE, |product, &n| product.checked_mul(n))
+            .ok_or(Overflow::Product(T::NAME))?;
+        Ok(Combination {
+            entries,
+            lines,
+            product,
+        })
+    }
+}
+
+/// How `sum_k` searches for entries. They all find a combination whenever there is one, but
+/// may find different ones when there's more than one.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Strategy {
+    /// Tries every combination in the order the entries appear: O(n^k).
+    Naive,
+    /// Picks all but the last entry, then looks up what's missing in a hash map of the entries
+    /// and their lines: O(n^(k-1)), which is a single O(n) pass for pairs.
+    Hash,
+    /// Picks all but the last two entries, then walks a pointer in from either end of the
+    /// sorted entries: O(n^(k-1)), which is O(n²) for triples.
+    TwoPointer,
+    /// Hashes every sum of half the entries, then looks up what's missing from each sum of the
+    /// other half: O(n^⌈k/2⌉), trading memory for time.
+    MeetInTheMiddle,
+}
+
+impl Strategy {
+    /// The quickest strategy for picking `k` entries.
+    pub fn fastest(k: usize) -> Self {
+        match k {
+            0..=2 => Strategy::Hash,
+            3 => Strategy::TwoPointer,
+            _ => Strategy::MeetInTheMiddle,
+        }
+    }
+}
+
+impl FromStr for Strategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "naive" => Ok(Strategy::Naive),
+            "hash" => Ok(Strategy::Hash),
+            "two-pointer" => Ok(Strategy::TwoPointer),
+            "meet-in-the-middle" => Ok(Strategy::MeetInTheMiddle),
+            _ => Err(format!(
+                "unknown strategy: '{}' (expected 'naive', 'hash', 'two-pointer' or \
+                 'meet-in-the-middle')",
+                s
+            )),
+        }
+    }
+}
+
+/// Finds `k` entries that sum to `target` with the fastest strategy for `k`. The same entry can
+/// be picked more than once.
+pub fn sum_k<T: Entry>(
+    numbers: &[T],
+    k: usize,
+    target: T,
+) -> Result<Option<Combination<T>>, Overflow> {
+    sum_k_with(numbers, k, target, Strategy::fastest(k))
+}
+
+/// Finds `k` entries that sum to `target` using the given strategy, in the order they appear
+/// in the report.
+///
+/// Sums and products are checked. When the target and every entry are non-negative, a sum that
+/// overflows is simply too big to hit the target. Otherwise, if `k` of the entries could add up
+/// to more than fits, that's reported before searching (whatever order a strategy would add them
+/// in), as is a product that overflows.
+pub fn sum_k_with<T: Entry>(
+    numbers: &[T],
+    k: usize,
+    target: T,
+    strategy: Strategy,
+) -> Result<Option<Combination<T>>, Overflow> {
+    let sums = Sums::new(numbers, k, target)?;
+    let entries = match strategy {
+        Strategy::Naive => naive(numbers, k, &sums)?,
+        // finds the lines as it goes
+        Strategy::Hash => return hash(numbers, k, &sums)?.map(with_lines).transpose(),
+        Strategy::TwoPointer => two_pointer(&sorted(numbers), k, &sums)?,
+        Strategy::MeetInTheMiddle => meet_in_the_middle(&sorted(numbers), k, &sums)?,
+    };
+
+    let entries = match entries {
+        Some(entries) => entries,
+        None => return Ok(None),
+    };
+    // an entry used more than once is put down to the first line it's on
+    let mut first = HashMap::new();
+    for (i, &n) in numbers.iter().enumerate().rev() {
+        first.insert(n, i + 1);
+    }
+    with_lines(entries.into_iter().map(|n| (first[&n], n)).collect()).map(Some)
+}
+
+/// The combination of entries found on the given lines, in the order they appear.
+fn with_lines<T: Entry>(mut found: Vec<(usize, T)>) -> Result<Combination<T>, Overflow> {
+    found.sort_unstable();
+    let (lines, entries) = found.into_iter().unzip();
+    Combination::new(entries, lines)
+}
+
+/// Finds `k` entries on different lines that sum to `target`.
+///
+/// This searches the sorted entries, finding the last with a binary search: O(n^(k-1) log n).
+pub fn sum_k_distinct<T: Entry>(
+    numbers: &[T],
+    k: usize,
+    target: T,
+) -> Result<Option<Combination<T>>, Overflow> {
+    let mut found = None;
+    distinct(numbers, k, target, &mut |combination| {
+        found = Some(combination);
+        Ok(true)
+    })?;
+    Ok(found)
+}
+
+/// Every set of `k` different lines whose entries sum to `target`, in no particular order.
+pub fn every_sum_k<T: Entry>(
+    numbers: &[T],
+    k: usize,
+    target: T,
+) -> Result<Vec<Combination<T>>, Overflow> {
+    let mut every = Vec::new();
+    distinct(numbers, k, target, &mut |combination| {
+        every.push(combination);
+        Ok(false)
+    })?;
+    Ok(every)
+}
+
+/// The puzzle's product of two entries summing to 2020.
+pub fn sum_two<T: Entry>(numbers: &[T]) -> Result<Option<T>, Overflow> {
+    puzzle(numbers, 2)
+}
+
+/// The puzzle's product of three entries summing to 2020.
+pub fn sum_three<T: Entry>(numbers: &[T]) -> Result<Option<T>, Overflow> {
+    puzzle(numbers, 3)
+}
+
+fn puzzle<T: Entry>(numbers: &[T], k: usize) -> Result<Option<T>, Overflow> {
+    let target = match T::try_from(TARGET) {
+        Ok(target) => target,
+        // no sum of these can ever get that high
+        Err(_) => return Ok(None),
+    };
+    Ok(sum_k(numbers, k, target)?.map(|c| c.product))
+}
+
+/// Keeps running sums of entries in check.
+struct Sums<T> {
+    target: T,
+    /// Whether sums can only grow, so one past the target can be dropped.
+    bounded: bool,
+}
+
+impl<T: Entry> Sums<T> {
+    /// Fails when sums can shrink and `k` of the smallest or biggest entry wouldn't fit, since
+    /// then whether a running sum overflowed would depend on the order entries were added in.
+    fn new(numbers: &[T], k: usize, target: T) -> Result<Self, Overflow> {
+        let bounded = target >= T::ZERO && numbers.iter().all(|&n| n >= T::ZERO);
+        let fits = |n: Option<&T>| {
+            n.is_none_or(|&n| {
+                (0..k)
+                    .try_fold(T::ZERO, |sum, _| sum.checked_add(n))
+                    .is_some()
+            })
+        };
+        if bounded || fits(numbers.iter().min()) && fits(numbers.iter().max()) {
+            Ok(Sums { target, bounded })
+        } else {
+            Err(Overflow::Sum(T::NAME))
+        }
+    }
+
+    /// Adds an entry to a running sum, or `None` when it can no longer reach the target. Only a
+    /// sum that can't shrink again can overflow, and then it's too big anyway.
+    fn add(&self, sum: T, n: T) -> Option<T> {
+        sum.checked_add(n)
+            .filter(|&sum| !(self.bounded && sum > self.target))
+    }
+
+    /// What's left to make up the target from a running sum, if any entry could.
+    fn rest(&self, sum: T) -> Option<T> {
+        self.target.checked_sub(sum)
+    }
+}
+
+fn naive<T: Entry>(numbers: &[T], k: usize, sums: &Sums<T>) -> Result<Option<Vec<T>>, Overflow> {
+    fn search<T: Entry>(
+        numbers: &[T],
+        k: usize,
+        sum: T,
+        sums: &Sums<T>,
+        picked: &mut Vec<T>,
+    ) -> Result<bool, Overflow> {
+        if k == 0 {
+            return Ok(sum == sums.target);
+        }
+        for &n in numbers {
+            let sum = match sums.add(sum, n) {
+                Some(sum) => sum,
+                None => continue,
+            };
+            picked.push(n);
+            if search(numbers, k - 1, sum, sums, picked)? {
+                return Ok(true);
+            }
+            picked.pop();
+        }
+        Ok(false)
+    }
+
+    let mut picked = Vec::with_capacity(k);
+    if search(numbers, k, T::ZERO, sums, &mut picked)? {
+        Ok(Some(picked))
+    } else {
+        Ok(None)
+    }
+}
+
+/// The distinct entries in ascending order; since entries can be reused, duplicates add nothing.
+fn sorted<T: Entry>(numbers: &[T]) -> Vec<T> {
+    let mut values = numbers.to_vec();
+    values.sort_unstable();
+    values.dedup();
+    values
+}
+
+/// Calls `f` with every way of picking `k` of the sorted `values` (repeats allowed, order
+/// ignored) whose sum can still reach the target, along with that sum, until it returns true.
+fn pick<T: Entry>(
+    values: &[T],
+    k: usize,
+    sums: &Sums<T>,
+    f: &mut impl FnMut(&[T], T) -> Result<bool, Overflow>,
+) -> Result<bool, Overflow> {
+    fn go<T: Entry>(
+        values: &[T],
+        start: usize,
+        k: usize,
+        sum: T,
+        sums: &Sums<T>,
+        picked: &mut Vec<T>,
+        f: &mut impl FnMut(&[T], T) -> Result<bool, Overflow>,
+    ) -> Result<bool, Overflow> {
+        if k == 0 {
+            return f(picked, sum);
+        }
+        for (i, &n) in values.iter().enumerate().skip(start) {
+            let sum = match sums.add(sum, n) {
+                Some(sum) => sum,
+                // the rest are bigger still
+                None => break,
+            };
+            picked.push(n);
+            if go(values, i, k - 1, sum, sums, picked, f)? {
+                return Ok(true);
+            }
+            picked.pop();
+        }
+        Ok(false)
+    }
+
+    go(values, 0, k, T::ZERO, sums, &mut Vec::with_capacity(k), f)
+}
+
+/// Looks up the last entry in a map from each value to the first line it's on. Pairs are found
+/// in a single pass, each entry looking for what it's missing among those read so far (itself
+/// included); more entries need the whole map first.
+fn hash<T: Entry>(
+    numbers: &[T],
+    k: usize,
+    sums: &Sums<T>,
+) -> Result<Option<Vec<(usize, T)>>, Overflow> {
+    if k == 0 {
+        return Ok(if sums.target == T::ZERO {
+            Some(Vec::new())
+        } else {
+            None
+        });
+    }
+
+    let mut lines: HashMap<T, usize> = HashMap::new();
+    if k <= 2 {
+        for (i, &n) in numbers.iter().enumerate() {
+            let line = *lines.entry(n).or_insert(i + 1);
+            let found = if k == 1 {
+                (n == sums.target).then(Vec::new)
+            } else {
+                let rest = sums.add(T::ZERO, n).and_then(|sum| sums.rest(sum));
+                rest.and_then(|rest| Some(vec![(*lines.get(&rest)?, rest)]))
+            };
+            if let Some(mut found) = found {
+                found.push((line, n));
+                return Ok(Some(found));
+            }
+        }
+        return Ok(None);
+    }
+
+    for (i, &n) in numbers.iter().enumerate() {
+        lines.entry(n).or_insert(i + 1);
+    }
+    // sorting is nothing next to picking the rest
+    let mut values: Vec<T> = lines.keys().copied().collect();
+    values.sort_unstable();
+    let mut found = None;
+    pick(&values, k - 1, sums, &mut |picked, sum| {
+        if let Some(rest) = sums.rest(sum).filter(|rest| lines.contains_key(rest)) {
+            let mut entries: Vec<(usize, T)> = picked.iter().map(|&n| (lines[&n], n)).collect();
+            entries.push((lines[&rest], rest));
+            found = Some(entries);
+        }
+        Ok(found.is_some())
+    })?;
+    Ok(found)
+}
+
+fn two_pointer<T: Entry>(
+    values: &[T],
+    k: usize,
+    sums: &Sums<T>,
+) -> Result<Option<Vec<T>>, Overflow> {
+    if k < 2 {
+        let found = hash(values, k, sums)?;
+        return Ok(found.map(|found| found.into_iter().map(|(_, n)| n).collect()));
+    }
+
+    let mut found = None;
+    pick(values, k - 2, sums, &mut |picked, sum| {
+        let rest = match sums.rest(sum) {
+            Some(rest) => rest,
+            None => return Ok(false),
+        };
+        let (mut lo, mut hi) = (0, values.len());
+        while hi > 0 && lo < hi {
+            match sums.add(values[lo], values[hi - 1]) {
+                Some(pair) if pair == rest => {
+                    let mut entries = picked.to_vec();
+                    entries.extend_from_slice(&[values[lo], values[hi - 1]]);
+                    found = Some(entries);
+                    return Ok(true);
+                }
+                Some(pair) if pair < rest => lo += 1,
+                _ => hi -= 1,
+            }
+        }
+        Ok(false)
+    })?;
+    Ok(found)
+}
+
+fn meet_in_the_middle<T: Entry>(
+    values: &[T],
+    k: usize,
+    sums: &Sums<T>,
+) -> Result<Option<Vec<T>>, Overflow> {
+    let half = k / 2;
+    let mut halves: HashMap<T, Vec<T>> = HashMap::new();
+    pick(values, half, sums, &mut |picked, sum| {
+        halves.entry(sum).or_insert_with(|| picked.to_vec());
+        Ok(false)
+    })?;
+
+    let mut found = None;
+    pick(values, k - half, sums, &mut |picked, sum| {
+        if let Some(other) = sums.rest(sum).and_then(|rest| halves.get(&rest)) {
+            let mut entries = other.clone();
+            entries.extend_from_slice(picked);
+            found = Some(entries);
+        }
+        Ok(found.is_some())
+    })?;
+    Ok(found)
+}
+
+/// Calls `f` with every combination of `k` different lines summing to `target`, until it
+/// returns true.
+fn distinct<T: Entry>(
+    numbers: &[T],
+    k: usize,
+    target: T,
+    f: &mut impl FnMut(Combination<T>) -> Result<bool, Overflow>,
+) -> Result<bool, Overflow> {
+    /// Picks entries at increasing positions in `sorted`, so each set of lines comes up once.
+    fn go<T: Entry>(
+        sorted: &[(T, usize)],
+        start: usize,
+        k: usize,
+        sum: T,
+        sums: &Sums<T>,
+        picked: &mut Vec<usize>,
+        f: &mut impl FnMut(Combination<T>) -> Result<bool, Overflow>,
+    ) -> Result<bool, Overflow> {
+        if k == 0 {
+            return if sum == sums.target {
+                f(combination(sorted, picked)?)
+            } else {
+                Ok(false)
+            };
+        }
+
+        if k == 1 {
+            // every entry that makes up the rest sits together in the sorted entries
+            let rest = match sums.rest(sum) {
+                Some(rest) => rest,
+                None => return Ok(false),
+            };
+            let from = start + sorted[start..].partition_point(|&(n, _)| n < rest);
+            for i in (from..sorted.len()).take_while(|&i| sorted[i].0 == rest) {
+                picked.push(i);
+                if f(combination(sorted, picked)?)? {
+                    return Ok(true);
+                }
+                picked.pop();
+            }
+            return Ok(false);
+        }
+
+        for i in start..sorted.len() {
+            let sum = match sums.add(sum, sorted[i].0) {
+                Some(sum) => sum,
+                // the rest are bigger still
+                None => break,
+            };
+            picked.push(i);
+            if go(sorted, i + 1, k - 1, sum, sums, picked, f)? {
+                return Ok(true);
+            }
+            picked.pop();
+        }
+        Ok(false)
+    }
+
+    /// The entries at the picked positions, in the order they appear in the report.
+    fn combination<T: Entry>(
+        sorted: &[(T, usize)],
+        picked: &[usize],
+    ) -> Result<Combination<T>, Overflow> {
+        let mut picked: Vec<(usize, T)> =
+            picked.iter().map(|&i| (sorted[i].1, sorted[i].0)).collect();
+        picked.sort_unstable();
+        let (lines, entries) = picked.into_iter().map(|(line, n)| (line + 1, n)).unzip();
+        Combination::new(entries, lines)
+    }
+
+    let mut sorted: Vec<(T, usize)> = numbers.iter().copied().zip(0..).collect();
+    sorted.sort_unstable();
+    go(
+        &sorted,
+        0,
+        k,
+        T::ZERO,
+        &Sums::new(numbers, k, target)?,
+        &mut Vec::with_capacity(k),
+        f,
+    )
+}
+
+fn parse_entry<T: Entry>(line: &str) -> Result<T, ParseError> {
+    line.parse::<T>().map_err(|e| {
+        ParseError::new(
+            line,
+            1,
+            format!("failed to parse line as {}: {}", T::NAME, e),
+        )
+    })
+}
+
+impl<T: Entry> Solution for ExpenseReport<T> {
+    type PartOne = T;
+    type PartTwo = T;
+
+    fn parse(input: &str) -> Result<Self, Error> {
+        let numbers = aoc::parse_lines(input, parse_entry)?;
+
+        Ok(Self { numbers })
+    }
+
+    fn part_one(&self) -> Result<Option<T>, Error> {
+        sum_two(&self.numbers).map_err(|e| Error::Solve(e.to_string()))
+    }
+
+    fn part_two(&self) -> Result<Option<T>, Error> {
+        sum_three(&self.numbers).map_err(|e| Error::Solve(e.to_string()))
+    }
+
+    fn details(&self) -> Value {
+        json!({ "entries": self.numbers.len() })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Strategy;
+    use super::*;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    /// Every product of `k` entries (repeats allowed) that sum to 2020, found the slow way.
+    fn brute_force(numbers: &[u64], k: usize) -> Vec<u64> {
+        let mut combos: Vec<(u64, u64)> = vec![(0, 1)];
+        for _ in 0..k {
+            combos = combos
+                .iter()
+                .flat_map(|&(sum, product)| numbers.iter().map(move |&n| (sum + n, product * n)))
+                .collect();
+        }
+        combos
+            .into_iter()
+            .filter(|&(sum, _)| sum == 2020)
+            .map(|(_, product)| product)
+            .collect()
+    }
+
+    const STRATEGIES: [Strategy; 4] = [
+        Strategy::Naive,
+        Strategy::Hash,
+        Strategy::TwoPointer,
+        Strategy::MeetInTheMiddle,
+    ];
+
+    #[test]
+    fn test_sum_k() {
+        let numbers: [i64; 6] = [1721, 979, 366, 299, 675, 1456];
+        let found = sum_k_with(&numbers, 2, 2020, Strategy::Naive)
+            .unwrap()
+            .unwrap();
+        assert_eq!(vec![1721, 299], found.entries);
+        assert_eq!(514579, found.product);
+
+        let found = sum_k_with(&numbers, 4, 3303, Strategy::Naive)
+            .unwrap()
+            .unwrap();
+        assert_eq!(vec![979, 979, 979, 366], found.entries);
+
+        for &strategy in STRATEGIES.iter() {
+            let found = |k, target| sum_k_with(&numbers, k, target, strategy).unwrap();
+            assert_eq!(514579, found(2, 2020).unwrap().product);
+            assert_eq!(241861950, found(3, 2020).unwrap().product);
+            assert_eq!(None, found(4, 3));
+            assert_eq!(
+                Some(Combination {
+                    entries: vec![],
+                    lines: vec![],
+                    product: 1
+                }),
+                found(0, 0)
+            );
+        }
+    }
+
+    #[test]
+    fn test_lines() {
+        let numbers: [i64; 6] = [1721, 979, 366, 299, 675, 1456];
+        for &strategy in STRATEGIES.iter() {
+            let found = sum_k_with(&numbers, 3, 2020, strategy).unwrap().unwrap();
+            let mut lines = found.lines.clone();
+            lines.sort_unstable();
+            assert_eq!(vec![2, 3, 5], lines);
+        }
+
+        let found = sum_k_with(&[5, 1010, 1010], 2, 2020, Strategy::Naive)
+            .unwrap()
+            .unwrap();
+        assert_eq!(vec![2, 2], found.lines);
+
+        // the hash search stops at the first entry that completes a pair
+        let found = sum_k_with(&[10, 1000, 1020, 2010, 1000], 2, 2020, Strategy::Hash)
+            .unwrap()
+            .unwrap();
+        assert_eq!((vec![1000, 1020], vec![2, 3]), (found.entries, found.lines));
+    }
+
+    #[test]
+    fn test_distinct() {
+        let numbers = [1010, 5, 2015];
+        assert!(sum_k(&numbers, 2, 2020).unwrap().is_some());
+        let found = sum_k_distinct(&numbers, 2, 2020).unwrap().unwrap();
+        assert_eq!((vec![5, 2015], vec![2, 3]), (found.entries, found.lines));
+
+        assert_eq!(None, sum_k_distinct(&[1010, 5], 2, 2020).unwrap());
+    }
+
+    #[test]
+    fn test_every() {
+        let numbers = [1010, 1000, 1010, 1020, 1010];
+        let mut every: Vec<Vec<usize>> = every_sum_k(&numbers, 2, 2020)
+            .unwrap()
+            .into_iter()
+            .map(|c| c.lines)
+            .collect();
+        every.sort();
+        assert_eq!(vec![vec![1, 3], vec![1, 5], vec![2, 4], vec![3, 5]], every);
+    }
+
+    #[test]
+    fn test_strategy() {
+        assert_eq!(Ok(Strategy::TwoPointer), "two-pointer".parse());
+        assert!("quick".parse::<Strategy>().is_err());
+    }
+
+    #[test]
+    fn test_negative() {
+        let numbers: [i32; 4] = [-500, 1200, 3000, -980];
+        for &strategy in STRATEGIES.iter() {
+            let found = sum_k_with(&numbers, 2, 2020, strategy).unwrap().unwrap();
+            assert_eq!(-2940000, found.product);
+        }
+    }
+
+    #[test]
+    fn test_overflow() {
+        for &strategy in STRATEGIES.iter() {
+            // too big to ever hit the target, which isn't a problem
+            assert_eq!(Ok(None), sum_k_with(&[200u8, 100], 2, 44, strategy));
+            assert_eq!(
+                Err(Overflow::Product("u8")),
+                sum_k_with(&[20u8, 30], 2, 50, strategy)
+            );
+        }
+
+        // with negative entries a sum that overflows could have come back down, so every strategy
+        // fails however it would have added them up
+        for &strategy in STRATEGIES.iter() {
+            assert_eq!(
+                Err(Overflow::Sum("i8")),
+                sum_k_with(&[100i8, -100], 3, 100, strategy)
+            );
+            assert_eq!(
+                Err(Overflow::Product("i8")),
+                sum_k_with(&[10i8, -20], 2, -10, strategy)
+            );
+        }
+        assert_eq!(
+            Err(Overflow::Sum("i8")),
+            sum_k_distinct(&[100i8, -100, 100], 3, 100)
+        );
+    }
+
+    #[test]
+    fn test_sample() {
+        let report = ExpenseReport::<i64>::parse("1721\n979\n366\n299\n675\n1456").unwrap();
+        assert_eq!(Some(514579), report.part_one().unwrap());
+        assert_eq!(Some(241861950), report.part_two().unwrap());
+    }
+
+    #[test]
+    fn test_parse_type() {
+        assert!(ExpenseReport::<u16>::parse("65535\n0").is_ok());
+        assert!(ExpenseReport::<u16>::parse("-1").is_err());
+        assert!(ExpenseReport::<i8>::parse("-128\n127").is_ok());
+        assert!(ExpenseReport::<i128>::parse(&i128::MIN.to_string()).is_ok());
+
+        // 2020 doesn't even fit
+        let report = ExpenseReport::<u8>::parse("1\n2").unwrap();
+        assert_eq!(None, report.part_one().unwrap());
+
+        // an answer that doesn't fit is an error, not a missing answer
+        let report = ExpenseReport::<u16>::parse("1010\n7").unwrap();
+        match report.part_one() {
+            Err(Error::Solve(e)) => assert_eq!("product of entries overflows u16", e),
+            _ => panic!("expected the product to overflow"),
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn prop_sum_two(numbers in vec(0u64..2100, 0..60)) {
+            let expected = brute_force(&numbers, 2);
+            match sum_two(&numbers).unwrap() {
+                Some(product) => prop_assert!(expected.contains(&product)),
+                None => prop_assert!(expected.is_empty()),
+            }
+        }
+
+        #[test]
+        fn prop_sum_k(numbers in vec(1u64..400, 0..12), k in 0usize..5, target in 0u64..1200) {
+            let expected = sum_k_with(&numbers, k, target, Strategy::Naive).unwrap().is_some();
+            for &strategy in STRATEGIES.iter() {
+                match sum_k_with(&numbers, k, target, strategy).unwrap() {
+                    Some(found) => {
+                        prop_assert_eq!(k, found.entries.len());
+                        prop_assert!(found.entries.iter().all(|n| numbers.contains(n)));
+                        prop_assert_eq!(target, found.entries.iter().sum::<u64>());
+                    }
+                    None => prop_assert!(!expected, "{:?} missed a combination", strategy),
+                }
+            }
+        }
+
+        #[test]
+        fn prop_sum_k_signed(
+            numbers in vec(-300i32..300, 0..12),
+            k in 0usize..4,
+            target in -900i32..900,
+        ) {
+            let expected = sum_k_with(&numbers, k, target, Strategy::Naive).unwrap().is_some();
+            for &strategy in STRATEGIES.iter() {
+                match sum_k_with(&numbers, k, target, strategy).unwrap() {
+                    Some(found) => {
+                        prop_assert_eq!(target, found.entries.iter().sum::<i32>());
+                        prop_assert_eq!(found.entries.iter().product::<i32>(), found.product);
+                    }
+                    None => prop_assert!(!expected, "{:?} missed a combination", strategy),
+                }
+            }
+        }
+
+        #[test]
+        fn prop_every_sum_k(numbers in vec(-20i32..40, 0..10), k in 0usize..4, target in -20i32..60) {
+            // every set of k lines, the slow way
+            fn count(numbers: &[i32], start: usize, k: usize, rest: i32) -> usize {
+                if k == 0 {
+                    return (rest == 0) as usize;
+                }
+                (start..numbers.len()).map(|i| count(numbers, i + 1, k - 1, rest - numbers[i])).sum()
+            }
+
+            let every = every_sum_k(&numbers, k, target).unwrap();
+            prop_assert_eq!(count(&numbers, 0, k, target), every.len());
+            for found in every.iter() {
+                prop_assert!(found.lines.windows(2).all(|w| w[0] < w[1]));
+                for (n, line) in found.entries.iter().zip(found.lines.iter()) {
+                    prop_assert_eq!(*n, numbers[line - 1]);
+                }
+                prop_assert_eq!(target, found.entries.iter().sum::<i32>());
+            }
+            prop_assert_eq!(!every.is_empty(), sum_k_distinct(&numbers, k, target).unwrap().is_some());
+        }
+
+        #[test]
+        fn prop_sum_three(numbers in vec(0u64..2100, 0..25)) {
+            let expected = brute_force(&numbers, 3);
+            match sum_three(&numbers).unwrap() {
+                Some(product) => prop_assert!(expected.contains(&product)),
+                None => prop_assert!(expected.is_empty()),
+            }
+        }
+    }
+}
@@ -1,61 +1,275 @@
-use std::env;
-use std::fs;
-use std::io;
-use std::io::BufRead;
-
-fn sum_two(numbers: &[u16]) -> Option<u64> {
-    for j in numbers {
-        for k in numbers {
-            if j + k == 2020 {
-                return Some((*j as u64) * (*k as u64));
-            }
+use aoc::{Args, Error};
+use day01::{Combination, Entry, ExpenseReport, Nearest, Pairs, Strategy};
+use std::io::{self, Write};
+use std::iter::FromIterator;
+
+fn main() {
+    aoc::main(|| {
+        let mut args = Args::from_env();
+        let ty = args.value::<String>("--type")?;
+
+        macro_rules! run_as {
+            ($($t:ty),*) => {
+                match ty.as_deref().unwrap_or("i64") {
+                    $(stringify!($t) => run::<$t>(args),)*
+                    ty => Err(invalid_input(format!(
+                        "unknown type: '{}' (expected {})", ty, [$(stringify!($t)),*].join(", "),
+                    ))),
+                }
+            };
         }
-    }
-    None
+        run_as!(i8, i16, i32, i64, i128, u8, u16, u32, u64, u128)
+    })
 }
 
-fn sum_three(numbers: &[u16]) -> Option<u64> {
-    for j in numbers {
-        for k in numbers {
-            for l in numbers {
-                if j + k + l == 2020 {
-                    return Some((*j as u64) * (*k as u64) * (*l as u64));
+/// Reads the entries as `T`, solving the puzzle or, when asked for a number of entries or a
+/// target, looking for that instead.
+fn run<T: Entry>(mut args: Args) -> Result<(), Error> {
+    let k = args.value::<usize>("--k")?;
+    let target = args.value::<T>("--target")?;
+    let strategy = args.value::<Strategy>("--strategy")?;
+    // only combine entries on different lines, optionally listing every combination that works
+    let all = args.flag("--all");
+    let distinct = args.flag("--distinct");
+    // the fewest entries, however many, that make up the target
+    let subset = args.flag("--subset");
+    let max_cells = args.value::<u128>("--max-cells")?;
+    // when nothing hits the target, settle for whatever comes closest (within the tolerance)
+    let tolerance = args.value::<u128>("--tolerance")?;
+    let nearest = args.flag("--nearest");
+    let streaming = args.flag("--stream");
+    args.reject_with_json(&[
+        ("--k", k.is_some()),
+        ("--target", target.is_some()),
+        ("--strategy", strategy.is_some()),
+        ("--distinct", distinct),
+        ("--all", all),
+        ("--subset", subset),
+        ("--max-cells", max_cells.is_some()),
+        ("--nearest", nearest),
+        ("--tolerance", tolerance.is_some()),
+        ("--stream", streaming),
+    ])?;
+    // each mode does its own search, so it'd ignore the flags that tune another one
+    Args::reject_with(
+        ("--stream", streaming),
+        "reads pairs a line at a time",
+        &[
+            ("--strategy", strategy.is_some()),
+            ("--subset", subset),
+            ("--nearest", nearest),
+            ("--tolerance", tolerance.is_some()),
+        ],
+    )?;
+    Args::reject_with(
+        ("--subset", subset),
+        "looks for the fewest entries, however many",
+        &[
+            ("--k", k.is_some()),
+            ("--strategy", strategy.is_some()),
+            ("--distinct", distinct),
+            ("--all", all),
+            ("--nearest", nearest),
+            ("--tolerance", tolerance.is_some()),
+        ],
+    )?;
+    Args::reject_with(
+        ("--strategy", strategy.is_some()),
+        "picks a search for entries that can repeat",
+        &[("--distinct", distinct), ("--all", all)],
+    )?;
+    if max_cells.is_some() && !subset {
+        return Err(invalid_input("--max-cells needs --subset"));
+    }
+    let distinct = distinct || all;
+    let max_cells = max_cells.unwrap_or(day01::MAX_CELLS);
+    let nearest = nearest || tolerance.is_some();
+
+    if streaming {
+        if k.is_some_and(|k| k != 2) {
+            return Err(invalid_input("--stream only looks for pairs"));
+        }
+        let target = match target {
+            Some(target) => target,
+            None => puzzle_target()?,
+        };
+        return stream(args, target, all);
+    }
+
+    aoc::for_each_input_in(args, day01::INPUT, |report: ExpenseReport<T>| {
+        let find = |k: usize, target: T| -> Result<Vec<Combination<T>>, Error> {
+            let found = if all {
+                day01::every_sum_k(&report.numbers, k, target)
+            } else if distinct {
+                day01::sum_k_distinct(&report.numbers, k, target).map(Vec::from_iter)
+            } else {
+                let strategy = strategy.unwrap_or_else(|| Strategy::fastest(k));
+                day01::sum_k_with(&report.numbers, k, target, strategy).map(Vec::from_iter)
+            };
+            found.map_err(|e| Error::Solve(e.to_string()))
+        };
+
+        if subset {
+            let target = match target {
+                Some(target) => target,
+                None => puzzle_target()?,
+            };
+            match day01::subset_sum(&report.numbers, target, max_cells)
+                .map_err(|e| Error::Solve(e.to_string()))?
+            {
+                Some(found) => println!("{}", describe(&found, target)),
+                None => println!("failed to find any lines with sum of {}", target),
+            }
+            return Ok(());
+        }
+
+        let searches = if k.is_some() || target.is_some() {
+            let target = match target {
+                Some(target) => target,
+                None => puzzle_target()?,
+            };
+            vec![(k.unwrap_or(2), target)]
+        } else {
+            let target = puzzle_target()?;
+            vec![(2, target), (3, target)]
+        };
+        // the puzzle's answers are just the products
+        let puzzle = k.is_none() && target.is_none() && !all;
+
+        for (k, target) in searches {
+            let found = find(k, target)?;
+            if found.is_empty() && nearest {
+                match day01::nearest_k(&report.numbers, k, target, tolerance, distinct)
+                    .map_err(|e| Error::Solve(e.to_string()))?
+                {
+                    Some(found) => println!("{}", describe_nearest(&found, target)),
+                    None => match tolerance {
+                        Some(tolerance) => println!(
+                            "failed to find {} lines with sum within {} of {}",
+                            k, tolerance, target
+                        ),
+                        None => println!("failed to find {} lines", k),
+                    },
                 }
+            } else if found.is_empty() && !all {
+                println!("failed to find {} lines with sum of {}", k, target);
+            }
+            for combination in found.iter() {
+                if puzzle {
+                    println!("{}", combination.product);
+                } else {
+                    println!("{}", describe(combination, target));
+                }
+            }
+            if all {
+                let plural = if found.len() == 1 { "" } else { "s" };
+                println!(
+                    "{} combination{} of {} lines with sum of {}",
+                    found.len(),
+                    plural,
+                    k,
+                    target
+                );
             }
         }
-    }
-    None
+        Ok(())
+    })
 }
 
-fn main() -> io::Result<()> {
-    let args: Vec<String> = env::args().collect();
-    if args.len() != 2 {
-        panic!("missing filename arg")
+/// Reads each input a line at a time, printing the first pair that sums to the target the moment
+/// it's read (or with `all`, every pair as it comes).
+fn stream<T: Entry>(args: Args, target: T, all: bool) -> Result<(), Error> {
+    for source in aoc::input::sources(args, day01::INPUT)? {
+        let mut found = false;
+        for pair in Pairs::new(source.reader()?, target) {
+            let pair = pair.map_err(|e| e.in_file(&source.name()))?;
+            let written = writeln!(io::stdout(), "{}", describe(&pair, target));
+            if written.is_err() {
+                // there's no going on once output fails, even when it's only a closed pipe
+                return aoc::ignore_broken_pipe(written);
+            }
+            found = true;
+            if !all {
+                break;
+            }
+        }
+        if !found {
+            println!("failed to find 2 lines with sum of {}", target);
+        }
     }
+    Ok(())
+}
 
-    let f = fs::File::open(&args[1])?;
-    let reader = io::BufReader::new(f);
+/// Spells out a combination with the line each entry came from, e.g.
+/// `1721 (line 1) + 299 (line 4) = 2020, product 514579`.
+fn describe<T: Entry>(found: &Combination<T>, target: T) -> String {
+    format!("{} = {}, product {}", terms(found), target, found.product)
+}
 
-    let numbers: Vec<u16> = reader
-        .lines()
-        .map(|l| {
-            l.expect("failed to read line")
-                .parse()
-                .expect("failed to parse line as u16")
-        })
+fn terms<T: Entry>(found: &Combination<T>) -> String {
+    let terms: Vec<String> = found
+        .entries
+        .iter()
+        .zip(found.lines.iter())
+        .map(|(n, line)| format!("{} (line {})", n, line))
         .collect();
+    terms.join(" + ")
+}
 
-    if let Some(s) = sum_two(&numbers) {
-        println!("{}", s);
-    } else {
-        println!("failed to find two lines with sum of 2020")
-    }
+/// Spells out a near miss like `describe`, with how far off it is, e.g.
+/// `1721 (line 1) + 300 (line 4) = 2021 (+1 from 2020), product 516300`.
+fn describe_nearest<T: Entry>(found: &Nearest<T>, target: T) -> String {
+    format!(
+        "{} = {} ({:+} from {}), product {}",
+        terms(&found.combination),
+        found.sum,
+        found.difference,
+        target,
+        found.combination.product
+    )
+}
+
+fn invalid_input(message: impl Into<String>) -> Error {
+    Error::Io(io::Error::new(io::ErrorKind::InvalidInput, message.into()))
+}
+
+fn puzzle_target<T: Entry>() -> Result<T, Error> {
+    T::try_from(day01::TARGET)
+        .map_err(|_| Error::Solve(format!("{} doesn't fit in {}", day01::TARGET, T::NAME)))
+}
 
-    if let Some(s) = sum_three(&numbers) {
-        println!("{}", s);
-    } else {
-        println!("failed to find three lines with sum of 2020")
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn run_with(args: &[&str]) -> Result<(), Error> {
+        run::<i64>(args.iter().copied().collect())
     }
 
-    Ok(())
+    #[test]
+    fn test_conflicting_modes() {
+        // all of these fail before any input is read
+        for args in [
+            &["--stream", "--subset"][..],
+            &["--stream", "--nearest"],
+            &["--stream", "--strategy", "hash"],
+            &["--subset", "--k", "3"],
+            &["--subset", "--all"],
+            &["--subset", "--tolerance", "5"],
+            &["--strategy", "naive", "--distinct"],
+            &["--max-cells", "10"],
+        ] {
+            let e = run_with(args).unwrap_err();
+            assert!(
+                matches!(&e, Error::Io(e) if e.kind() == io::ErrorKind::InvalidInput),
+                "{:?}: {}",
+                args,
+                e
+            );
+        }
+        assert_eq!(
+            "--stream reads pairs a line at a time, so it can't be used with --subset",
+            run_with(&["--stream", "--subset"]).unwrap_err().to_string()
+        );
+    }
 }
@@ -0,0 +1,250 @@
+use crate::{Combination, Entry, Overflow};
+
+/// A combination of entries that comes as close to the target as any.
+#[derive(Debug, PartialEq)]
+pub struct Nearest<T> {
+    pub combination: Combination<T>,
+    pub sum: i128,
+    /// How far the sum is from the target: positive when over, negative when under.
+    pub difference: i128,
+}
+
+/// Finds the `k` entries whose sum is closest to `target`, for when none hit it exactly. They're
+/// only returned when within `tolerance` of it, if there is one. With `distinct` the entries have
+/// to be on different lines, otherwise the same one can be picked more than once.
+///
+/// Like `Strategy::TwoPointer` this picks all but the last two of the sorted entries and walks
+/// in from either end for those: O(n^(k-1)). Sums are worked out as `i128`.
+pub fn nearest_k<T: Entry>(
+    numbers: &[T],
+    k: usize,
+    target: T,
+    tolerance: Option<u128>,
+    distinct: bool,
+) -> Result<Option<Nearest<T>>, Overflow> {
+    let overflow = Overflow::Sum(T::NAME);
+    let wide = |n: T| n.try_into().map_err(|_| overflow);
+    let target = wide(target)?;
+    let mut sorted = numbers
+        .iter()
+        .enumerate()
+        .map(|(i, &n)| Ok((wide(n)?, i)))
+        .collect::<Result<Vec<(i128, usize)>, Overflow>>()?;
+    sorted.sort_unstable();
+
+    let search = Search {
+        sorted: &sorted,
+        target,
+        distinct,
+        overflow,
+    };
+    let mut best = None;
+    search.go(0, k, 0, &mut Vec::with_capacity(k), &mut best)?;
+
+    let (difference, picked) = match best {
+        Some(best) => best,
+        None => return Ok(None),
+    };
+    if tolerance.is_some_and(|tolerance| difference.unsigned_abs() > tolerance) {
+        return Ok(None);
+    }
+
+    let mut picked: Vec<usize> = picked.into_iter().map(|at| sorted[at].1).collect();
+    picked.sort_unstable();
+    let entries = picked.iter().map(|&i| numbers[i]).collect();
+    let lines = picked.iter().map(|i| i + 1).collect();
+    Ok(Some(Nearest {
+        combination: Combination::new(entries, lines)?,
+        sum: target + difference,
+        difference,
+    }))
+}
+
+struct Search<'a> {
+    sorted: &'a [(i128, usize)],
+    target: i128,
+    distinct: bool,
+    overflow: Overflow,
+}
+
+/// The best difference so far, with the positions in `sorted` that make it.
+type Best = Option<(i128, Vec<usize>)>;
+
+impl Search<'_> {
+    /// Picks entries at positions from `start` on, keeping the closest combination in `best`.
+    /// Returns true once there's no beating it.
+    fn go(
+        &self,
+        start: usize,
+        k: usize,
+        sum: i128,
+        picked: &mut Vec<usize>,
+        best: &mut Best,
+    ) -> Result<bool, Overflow> {
+        match k {
+            0 => self.offer(sum, picked, best),
+            1 | 2 => self.ends(start, k, sum, picked, best),
+            _ => {
+                for i in start..self.sorted.len() {
+                    let sum = sum.checked_add(self.sorted[i].0).ok_or(self.overflow)?;
+                    picked.push(i);
+                    let next = if self.distinct { i + 1 } else { i };
+                    if self.go(next, k - 1, sum, picked, best)? {
+                        return Ok(true);
+                    }
+                    picked.pop();
+                }
+                Ok(false)
+            }
+        }
+    }
+
+    /// Finds the closest last one or two entries, walking in from either end of what's left.
+    fn ends(
+        &self,
+        start: usize,
+        k: usize,
+        sum: i128,
+        picked: &mut Vec<usize>,
+        best: &mut Best,
+    ) -> Result<bool, Overflow> {
+        let needed = if k == 2 && self.distinct { 2 } else { 1 };
+        if start + needed > self.sorted.len() {
+            return Ok(false);
+        }
+        let (mut lo, mut hi) = (start, self.sorted.len() - 1);
+        loop {
+            // with one left to pick, both ends just take turns at being it
+            let (a, b) = if k == 1 { (hi, None) } else { (lo, Some(hi)) };
+            let mut total = sum.checked_add(self.sorted[a].0).ok_or(self.overflow)?;
+            if let Some(b) = b {
+                total = total.checked_add(self.sorted[b].0).ok_or(self.overflow)?;
+            }
+
+            let at = picked.len();
+            picked.push(a);
+            picked.extend(b);
+            let exact = self.offer(total, picked, best)?;
+            picked.truncate(at);
+            if exact {
+                return Ok(true);
+            }
+
+            let last = if k == 2 && self.distinct {
+                lo + 1 >= hi
+            } else {
+                lo >= hi
+            };
+            if last {
+                return Ok(false);
+            }
+            if k == 1 {
+                hi -= 1;
+            } else if total < self.target {
+                lo += 1;
+            } else {
+                hi -= 1;
+            }
+        }
+    }
+
+    /// Keeps the combination if it's the closest yet, returning whether it's spot on.
+    fn offer(&self, sum: i128, picked: &[usize], best: &mut Best) -> Result<bool, Overflow> {
+        let difference = sum.checked_sub(self.target).ok_or(self.overflow)?;
+        let closer = match best {
+            Some((d, _)) => difference.unsigned_abs() < d.unsigned_abs(),
+            None => true,
+        };
+        if closer {
+            *best = Some((difference, picked.to_vec()));
+        }
+        Ok(difference == 0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_nearest() {
+        let numbers: [i64; 6] = [1721, 979, 366, 299, 675, 1456];
+
+        let found = nearest_k(&numbers, 2, 2020, None, false).unwrap().unwrap();
+        assert_eq!((vec![1, 4], 0), (found.combination.lines, found.difference));
+
+        // 1456 + 675 = 2131 is the closest over, 1721 + 299 = 2020 the closest under
+        let found = nearest_k(&numbers, 2, 2025, None, true).unwrap().unwrap();
+        assert_eq!((2020, -5), (found.sum, found.difference));
+        assert_eq!(514579, found.combination.product);
+
+        let found = nearest_k(&numbers, 1, 1800, None, false).unwrap().unwrap();
+        assert_eq!(
+            (vec![1721], -79),
+            (found.combination.entries, found.difference)
+        );
+
+        let found = nearest_k(&numbers, 3, 6000, None, false).unwrap().unwrap();
+        assert_eq!(
+            (vec![1721, 1721, 1721], -837),
+            (found.combination.entries, found.difference)
+        );
+        let found = nearest_k(&numbers, 3, 6000, None, true).unwrap().unwrap();
+        assert_eq!(4156 - 6000, found.difference);
+    }
+
+    #[test]
+    fn test_tolerance() {
+        let numbers: [u32; 3] = [1000, 1003, 500];
+        let found = nearest_k(&numbers, 2, 2020, Some(20), true)
+            .unwrap()
+            .unwrap();
+        assert_eq!(-17, found.difference);
+        assert_eq!(None, nearest_k(&numbers, 2, 2020, Some(16), true).unwrap());
+    }
+
+    #[test]
+    fn test_too_few() {
+        assert_eq!(None, nearest_k(&[1010u32], 2, 2020, None, true).unwrap());
+        assert_eq!(None, nearest_k::<u32>(&[], 1, 2020, None, false).unwrap());
+        let found = nearest_k(&[1010u32], 2, 2020, None, false)
+            .unwrap()
+            .unwrap();
+        assert_eq!(0, found.difference);
+    }
+
+    proptest! {
+        #[test]
+        fn prop_nearest(
+            numbers in vec(-50i32..50, 0..8),
+            k in 0usize..4,
+            target in -200i32..200,
+            distinct in any::<bool>(),
+        ) {
+            // every combination, the slow way
+            fn closest(numbers: &[i32], start: usize, k: usize, sum: i32, target: i32, distinct: bool) -> Option<u32> {
+                if k == 0 {
+                    return Some((sum - target).unsigned_abs());
+                }
+                (start..numbers.len())
+                    .filter_map(|i| closest(numbers, if distinct { i + 1 } else { i }, k - 1, sum + numbers[i], target, distinct))
+                    .min()
+            }
+
+            let expected = closest(&numbers, 0, k, 0, target, distinct);
+            let found = nearest_k(&numbers, k, target, None, distinct).unwrap();
+            prop_assert_eq!(expected, found.as_ref().map(|f| f.difference.unsigned_abs() as u32));
+            if let Some(found) = found {
+                let sum: i32 = found.combination.entries.iter().sum();
+                prop_assert_eq!(found.sum, sum as i128);
+                prop_assert_eq!(found.difference, (sum - target) as i128);
+                prop_assert_eq!(k, found.combination.entries.len());
+                if distinct {
+                    prop_assert!(found.combination.lines.windows(2).all(|w| w[0] < w[1]));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use crate::{parse_entry, Combination, Entry};
+use aoc::Error;
+use std::collections::HashMap;
+use std::io::{BufRead, Lines};
+
+/// Pairs of entries on different lines that sum to a target, found while reading the entries a
+/// line at a time.
+///
+/// Each pair comes out as soon as the line completing it is read, paired with the first line
+/// that completes it, without reading any further. That makes it fit for inputs that never end,
+/// like a pipe from a live log.
+pub struct Pairs<T, R> {
+    lines: Lines<R>,
+    target: T,
+    /// The first line each entry read so far is on.
+    seen: HashMap<T, usize>,
+    line: usize,
+    failed: bool,
+}
+
+impl<T: Entry, R: BufRead> Pairs<T, R> {
+    pub fn new(reader: R, target: T) -> Self {
+        Pairs {
+            lines: reader.lines(),
+            target,
+            seen: HashMap::new(),
+            line: 0,
+            failed: false,
+        }
+    }
+}
+
+impl<T: Entry, R: BufRead> Iterator for Pairs<T, R> {
+    type Item = Result<Combination<T>, Error>;
+
+    /// Reads up to the next line that completes a pair. Stops for good after an error.
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.failed {
+            let text = match self.lines.next()? {
+                Ok(text) => text,
+                Err(e) => {
+                    self.failed = true;
+                    return Some(Err(e.into()));
+                }
+            };
+            self.line += 1;
+
+            let n = match parse_entry::<T>(&text) {
+                Ok(n) => n,
+                Err(e) => {
+                    self.failed = true;
+                    return Some(Err(e.at_line(self.line).into()));
+                }
+            };
+
+            let earlier = self
+                .target
+                .checked_sub(n)
+                .and_then(|rest| self.seen.get(&rest).map(|&line| (rest, line)));
+            self.seen.entry(n).or_insert(self.line);
+
+            if let Some((rest, line)) = earlier {
+                let found = Combination::new(vec![rest, n], vec![line, self.line])
+                    .map_err(|e| Error::Solve(e.to_string()));
+                self.failed = found.is_err();
+                return Some(found);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::io::{self, BufReader, Read};
+
+    /// Fails any read, standing in for input that hasn't arrived yet.
+    struct Unread;
+
+    impl Read for Unread {
+        fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+            Err(io::Error::other("read too far"))
+        }
+    }
+
+    #[test]
+    fn test_pairs() {
+        let input = "1721\n979\n366\n299\n675\n1456\n1010\n1010\n";
+        let found: Vec<Vec<usize>> = Pairs::new(input.as_bytes(), 2020i64)
+            .map(|c| c.unwrap().lines)
+            .collect();
+        assert_eq!(vec![vec![1, 4], vec![7, 8]], found);
+    }
+
+    #[test]
+    fn test_stops_reading() {
+        let reader = BufReader::new("979\n2000\n1041\n".as_bytes().chain(Unread));
+        let mut pairs = Pairs::new(reader, 2020u32);
+
+        let found = pairs.next().unwrap().unwrap();
+        assert_eq!((vec![979, 1041], vec![1, 3]), (found.entries, found.lines));
+        assert!(pairs.next().unwrap().is_err());
+        assert!(pairs.next().is_none());
+    }
+
+    #[test]
+    fn test_malformed() {
+        let mut pairs = Pairs::new("1000\nabc\n1020\n".as_bytes(), 2020u16);
+        match pairs.next() {
+            Some(Err(Error::Parse(errors))) => assert_eq!(2, errors[0].line),
+            _ => panic!("expected a parse error"),
+        }
+        assert!(pairs.next().is_none());
+    }
+}
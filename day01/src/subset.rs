@@ -0,0 +1,239 @@
+use crate::{Combination, Entry, Overflow};
+use std::{error, fmt};
+
+/// The most cells `subset_sum` builds its table with unless asked otherwise. A cell is a bit,
+/// and each sum takes one per entry plus 32 for the fewest entries making it, so about 12MB.
+pub const MAX_CELLS: u128 = 100_000_000;
+
+/// The cells each sum takes on top of one per entry: the `u32` counting the fewest entries
+/// making it.
+const COUNT_CELLS: i128 = 32;
+
+/// Why `subset_sum` couldn't answer.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SubsetError {
+    Overflow(Overflow),
+    /// The table would need more cells than allowed (`None` when too many to even count).
+    TooLarge {
+        cells: Option<u128>,
+        max: u128,
+    },
+}
+
+impl fmt::Display for SubsetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SubsetError::Overflow(e) => write!(f, "{}", e),
+            SubsetError::TooLarge {
+                cells: Some(cells),
+                max,
+            } => write!(
+                f,
+                "subset sum table would need {} cells, more than the {} allowed",
+                cells, max
+            ),
+            SubsetError::TooLarge { cells: None, max } => write!(
+                f,
+                "subset sum table would need too many cells to count, more than the {} allowed",
+                max
+            ),
+        }
+    }
+}
+
+impl error::Error for SubsetError {}
+
+impl From<Overflow> for SubsetError {
+    fn from(e: Overflow) -> Self {
+        SubsetError::Overflow(e)
+    }
+}
+
+/// Finds the fewest entries on different lines that sum to `target`, however many that takes.
+///
+/// This builds a table with a row per entry and a column per sum the entries could make along
+/// the way: from 0 to the target when nothing's negative, otherwise from the sum of every
+/// negative entry to the sum of every positive one. That's O(n × sums) time and space, so
+/// rather than run for ever it fails when the table would have more than `max_cells` cells.
+pub fn subset_sum<T: Entry>(
+    numbers: &[T],
+    target: T,
+    max_cells: u128,
+) -> Result<Option<Combination<T>>, SubsetError> {
+    let too_large = |cells| SubsetError::TooLarge {
+        cells,
+        max: max_cells,
+    };
+
+    let values: Vec<i128> = numbers
+        .iter()
+        .map(|&n| n.try_into().ok())
+        .collect::<Option<_>>()
+        .ok_or_else(|| too_large(None))?;
+    let target: i128 = target.try_into().ok().ok_or_else(|| too_large(None))?;
+
+    let total = |keep: fn(&i128) -> bool| {
+        values
+            .iter()
+            .copied()
+            .filter(keep)
+            .try_fold(0i128, i128::checked_add)
+    };
+    let (lo, hi) = match (total(|&n| n < 0), total(|&n| n > 0)) {
+        (Some(0), Some(hi)) => (0, hi.min(target)),
+        (Some(lo), Some(hi)) => (lo, hi),
+        _ => return Err(too_large(None)),
+    };
+    if target < lo || target > hi {
+        return Ok(None);
+    }
+
+    let width = (hi - lo)
+        .checked_add(1)
+        .and_then(|w| w.checked_mul(values.len() as i128 + COUNT_CELLS))
+        .map(|cells| cells as u128);
+    match width {
+        Some(cells) if cells <= max_cells => {}
+        cells => return Err(too_large(cells)),
+    }
+    let width = (hi - lo + 1) as usize;
+    let column = |sum: i128| (sum - lo) as usize;
+
+    // the fewest entries making up each sum so far, and for each entry the sums it made better
+    const NONE: u32 = u32::MAX;
+    let mut fewest = vec![NONE; width];
+    fewest[column(0)] = 0;
+    let words = width.div_ceil(64);
+    let mut took = vec![0u64; values.len() * words];
+
+    for (i, &n) in values.iter().enumerate() {
+        let row = &mut took[i * words..(i + 1) * words];
+        let mut add = |to: usize| {
+            let from = to as i128 - n;
+            if from < 0 || from >= width as i128 {
+                return;
+            }
+            let count = fewest[from as usize];
+            if count != NONE && count + 1 < fewest[to] {
+                fewest[to] = count + 1;
+                row[to / 64] |= 1 << (to % 64);
+            }
+        };
+        // go against the direction the entry moves sums in, so it's only used once
+        if n >= 0 {
+            (0..width).rev().for_each(&mut add);
+        } else {
+            (0..width).for_each(&mut add);
+        }
+    }
+
+    let mut at = column(target);
+    if fewest[at] == NONE {
+        return Ok(None);
+    }
+
+    let mut lines = Vec::new();
+    for (i, &n) in values.iter().enumerate().rev() {
+        if took[i * words + at / 64] >> (at % 64) & 1 == 1 {
+            lines.push(i + 1);
+            at = (at as i128 - n) as usize;
+        }
+    }
+    lines.reverse();
+    let entries = lines.iter().map(|line| numbers[line - 1]).collect();
+    Ok(Some(Combination::new(entries, lines)?))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_subset_sum() {
+        let numbers: [i64; 6] = [1721, 979, 366, 299, 675, 1456];
+        let found = subset_sum(&numbers, 2020, MAX_CELLS).unwrap().unwrap();
+        assert_eq!((vec![1721, 299], vec![1, 4]), (found.entries, found.lines));
+
+        let found = subset_sum(&numbers, 4156, MAX_CELLS).unwrap().unwrap();
+        assert_eq!(3, found.entries.len());
+        assert_eq!(4156, found.entries.iter().sum::<i64>());
+
+        assert_eq!(None, subset_sum(&numbers, 4, MAX_CELLS).unwrap());
+        assert_eq!(
+            0,
+            subset_sum(&numbers, 0, MAX_CELLS)
+                .unwrap()
+                .unwrap()
+                .entries
+                .len()
+        );
+    }
+
+    #[test]
+    fn test_negative() {
+        let found = subset_sum(&[-30i32, 10, 45, -5], 10, MAX_CELLS)
+            .unwrap()
+            .unwrap();
+        assert_eq!(vec![2], found.lines);
+
+        let found = subset_sum(&[-30i32, 10, 45, -5], 15, MAX_CELLS)
+            .unwrap()
+            .unwrap();
+        assert_eq!(vec![1, 3], found.lines);
+
+        let found = subset_sum(&[-30i32, 10, 45, -5], -35, MAX_CELLS)
+            .unwrap()
+            .unwrap();
+        assert_eq!(vec![1, 4], found.lines);
+    }
+
+    #[test]
+    fn test_too_large() {
+        assert_eq!(
+            Err(SubsetError::TooLarge {
+                cells: Some(34 * 1_000_001),
+                max: 1000
+            }),
+            subset_sum(&[600_000u32, 400_000], 1_000_000, 1000)
+        );
+        // a single entry still needs a count for every sum
+        assert_eq!(
+            Err(SubsetError::TooLarge {
+                cells: Some(33 * 50_000_001),
+                max: MAX_CELLS
+            }),
+            subset_sum(&[50_000_000u32], 50_000_000, MAX_CELLS)
+        );
+        assert!(subset_sum(&[3_000_000u32], 3_000_000, MAX_CELLS).is_ok());
+        assert_eq!(
+            Err(SubsetError::TooLarge {
+                cells: None,
+                max: MAX_CELLS
+            }),
+            subset_sum(&[u128::MAX], 1, MAX_CELLS)
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn prop_fewest(numbers in vec(-20i64..40, 0..10), target in -30i64..80) {
+            // every subset, the slow way
+            let fewest = (0u32..1 << numbers.len())
+                .filter(|set| {
+                    let sum: i64 = (0..numbers.len()).filter(|i| set >> i & 1 == 1).map(|i| numbers[i]).sum();
+                    sum == target
+                })
+                .map(|set| set.count_ones() as usize)
+                .min();
+
+            let found = subset_sum(&numbers, target, MAX_CELLS).unwrap();
+            prop_assert_eq!(fewest, found.as_ref().map(|c| c.entries.len()));
+            if let Some(found) = found {
+                prop_assert!(found.lines.windows(2).all(|w| w[0] < w[1]));
+                prop_assert_eq!(target, found.entries.iter().sum::<i64>());
+            }
+        }
+    }
+}
@@ -0,0 +1,296 @@
+use crate::{PasswordEntry, PasswordPolicy, Unit, Verdict, Violation};
+use aoc::rand::seq::SliceRandom;
+use aoc::rand::Rng;
+use std::fmt;
+use std::str::FromStr;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// How many random entries `fixture` tries before deciding the policy can't give what's wanted.
+pub const TRIES: usize = 100_000;
+
+/// The shortest maximum length `fixture` tries passwords up to, whatever the hints say.
+const LENGTH: usize = 20;
+
+/// What a policy's fixture generator should aim for beyond lowercase letters and the target.
+#[derive(Debug, Default)]
+pub struct Hints {
+    /// Units to mix into passwords, e.g. digits for a rule that wants some.
+    pub units: Vec<String>,
+    /// Text to drop into passwords whole, e.g. a substring a rule forbids.
+    pub texts: Vec<String>,
+    /// The longest password worth trying.
+    pub length: usize,
+}
+
+impl Hints {
+    pub fn units(&mut self, units: &str) {
+        let units = units.graphemes(true).filter(|u| !u.trim().is_empty());
+        self.units.extend(units.map(String::from));
+    }
+
+    /// Offers text to drop in whole, unless it has whitespace no password on a line can have.
+    pub fn text(&mut self, text: &str) {
+        if !text.contains(char::is_whitespace) {
+            self.texts.push(String::from(text));
+        }
+    }
+
+    pub fn length(&mut self, length: usize) {
+        self.length = self.length.max(length);
+    }
+}
+
+/// Which way a fixture should break its policy.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Flaw {
+    /// However it likes.
+    Any,
+    /// Too few of the target, of a rule's set, or of units altogether.
+    TooFew,
+    /// Too many of any of those.
+    TooMany,
+    /// Both positions hold the target.
+    Both,
+    /// A position that should hold the target (or a unit of a rule's set) doesn't.
+    Neither,
+    /// It contains text a rule forbids.
+    Forbidden,
+}
+
+impl Flaw {
+    const ALL: [Flaw; 6] = [
+        Flaw::Any,
+        Flaw::TooFew,
+        Flaw::TooMany,
+        Flaw::Both,
+        Flaw::Neither,
+        Flaw::Forbidden,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Flaw::Any => "any",
+            Flaw::TooFew => "too-few",
+            Flaw::TooMany => "too-many",
+            Flaw::Both => "both",
+            Flaw::Neither => "neither",
+            Flaw::Forbidden => "forbidden",
+        }
+    }
+
+    /// Whether the violation is (or, for a combination, includes) this flaw.
+    pub fn matches(self, violation: &Violation) -> bool {
+        match (self, violation) {
+            (Flaw::Any, _) => true,
+            (_, Violation::All(vs)) | (_, Violation::Any(vs)) => vs.iter().any(|v| self.matches(v)),
+            (Flaw::TooFew, Violation::Count { count, left, .. }) => count < left,
+            (Flaw::TooFew, Violation::Has { count, range, .. }) => *count < range.min,
+            (Flaw::TooFew, Violation::Length { length, range }) => *length < range.min,
+            (Flaw::TooMany, Violation::Count { count, right, .. }) => count > right,
+            (Flaw::TooMany, Violation::Has { count, range, .. }) => {
+                range.max.is_some_and(|max| *count > max)
+            }
+            (Flaw::TooMany, Violation::Length { length, range }) => {
+                range.max.is_some_and(|max| *length > max)
+            }
+            (Flaw::Both, Violation::BothPositions { .. }) => true,
+            (Flaw::Neither, Violation::NeitherPosition { .. }) => true,
+            (Flaw::Neither, Violation::At { .. }) => true,
+            (Flaw::Forbidden, Violation::Forbidden(_)) => true,
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for Flaw {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Flaw::ALL
+            .iter()
+            .copied()
+            .find(|f| f.name() == s)
+            .ok_or_else(|| {
+                let names: Vec<&str> = Flaw::ALL.iter().map(|f| f.name()).collect();
+                format!("expected {}", names.join(", "))
+            })
+    }
+}
+
+/// Whether a fixture should pass its policy, or fail it and how.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Outcome {
+    Pass,
+    Fail(Flaw),
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Pass => f.write_str("passes"),
+            Outcome::Fail(Flaw::Any) => f.write_str("fails"),
+            Outcome::Fail(flaw) => write!(f, "fails with {}", flaw.name()),
+        }
+    }
+}
+
+impl Outcome {
+    fn wants(self, verdict: &Verdict) -> bool {
+        match (self, verdict) {
+            (Outcome::Pass, Verdict::Valid) => true,
+            (Outcome::Fail(flaw), Verdict::Invalid(violation)) => flaw.matches(violation),
+            _ => false,
+        }
+    }
+}
+
+/// Builds a random line that passes or fails the policy as asked, in the format
+/// `PasswordEntry::parse` reads back.
+///
+/// It throws random entries at the policy until one comes out right, mixing in whatever the
+/// policy hints at, and gives up after `TRIES` of them: some policies (like `has 1+ space`,
+/// which no password on a line can pass) never will.
+pub fn fixture<R: Rng>(
+    rng: &mut R,
+    policy: &dyn PasswordPolicy,
+    outcome: Outcome,
+    unit: Unit,
+) -> Option<String> {
+    let mut hints = Hints::default();
+    policy.hint(&mut hints);
+    let longest = hints.length.max(LENGTH);
+    let letters: Vec<String> = ('a'..='z').map(String::from).collect();
+
+    for _ in 0..TRIES {
+        // weigh the kinds of unit differently every time, so lopsided passwords turn up too
+        let weights = [
+            rng.gen_range(0..4),
+            rng.gen_range(0..4),
+            if hints.units.is_empty() {
+                0
+            } else {
+                rng.gen_range(0..4)
+            },
+            if hints.texts.is_empty() {
+                0
+            } else {
+                rng.gen_range(0..2)
+            },
+        ];
+        let total: u32 = weights.iter().sum();
+        if total == 0 {
+            continue;
+        }
+
+        let target = if weights[2] > 0 && rng.gen() {
+            hints.units.choose(rng)
+        } else {
+            letters.choose(rng)
+        }
+        .unwrap()
+        .clone();
+
+        let len = rng.gen_range(1..=longest);
+        let mut password = String::new();
+        while unit.split(&password).count() < len {
+            let mut pick = rng.gen_range(0..total);
+            let kind = weights
+                .iter()
+                .position(|&w| {
+                    if pick < w {
+                        return true;
+                    }
+                    pick -= w;
+                    false
+                })
+                .unwrap();
+            password.push_str(match kind {
+                0 => letters.choose(rng).unwrap(),
+                1 => &target,
+                2 => hints.units.choose(rng).unwrap(),
+                _ => hints.texts.choose(rng).unwrap(),
+            });
+        }
+
+        let len = unit.split(&password).count();
+        let left = rng.gen_range(1..=len);
+        let right = rng.gen_range(left..=len);
+        let entry = PasswordEntry {
+            left,
+            right,
+            target: &target,
+            password: &password,
+            unit,
+            rule: None,
+        };
+        if outcome.wants(&policy.check(&entry)) {
+            return Some(entry.to_string());
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{ByCount, ByIndex, Rule};
+    use aoc::rand::rngs::StdRng;
+    use aoc::rand::SeedableRng;
+
+    fn check(policy: &dyn PasswordPolicy, outcome: Outcome) {
+        let mut rng = StdRng::seed_from_u64(2020);
+        for _ in 0..50 {
+            let line = fixture(&mut rng, policy, outcome, Unit::Chars)
+                .unwrap_or_else(|| panic!("no fixture that {}", outcome));
+            let parsed = PasswordEntry::parse(&line).unwrap();
+            assert_eq!(line, parsed.to_string());
+            assert!(outcome.wants(&policy.check(&parsed)), "{}", line);
+        }
+    }
+
+    #[test]
+    fn test_puzzle_policies() {
+        for outcome in [
+            Outcome::Pass,
+            Outcome::Fail(Flaw::Any),
+            Outcome::Fail(Flaw::TooFew),
+            Outcome::Fail(Flaw::TooMany),
+        ] {
+            check(&ByCount, outcome);
+        }
+        for outcome in [
+            Outcome::Pass,
+            Outcome::Fail(Flaw::Both),
+            Outcome::Fail(Flaw::Neither),
+        ] {
+            check(&ByIndex, outcome);
+        }
+    }
+
+    #[test]
+    fn test_rule() {
+        let rule: Rule = "min-length 12 and has 2+ digit and has 1+ upper and has 1-2 \"!?\" \
+                          and at 1 alpha and forbid \"pass\" and count"
+            .parse()
+            .unwrap();
+        for outcome in [
+            Outcome::Pass,
+            Outcome::Fail(Flaw::TooFew),
+            Outcome::Fail(Flaw::TooMany),
+            Outcome::Fail(Flaw::Neither),
+            Outcome::Fail(Flaw::Forbidden),
+        ] {
+            check(&rule, outcome);
+        }
+    }
+
+    #[test]
+    fn test_impossible() {
+        let mut rng = StdRng::seed_from_u64(2020);
+        // nothing fails the count policy by having both positions hold the target
+        assert!(fixture(&mut rng, &ByCount, Outcome::Fail(Flaw::Both), Unit::Chars).is_none());
+        let rule: Rule = "has 1+ space".parse().unwrap();
+        assert!(fixture(&mut rng, &rule, Outcome::Pass, Unit::Chars).is_none());
+    }
+}
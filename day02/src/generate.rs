@@ -0,0 +1,79 @@
+use crate::PasswordDatabase;
+use aoc::rand::seq::index;
+use aoc::rand::Rng;
+use aoc::{Generate, Generated};
+
+fn letter<R: Rng>(rng: &mut R) -> char {
+    (b'a' + rng.gen_range(0..26)) as char
+}
+
+impl Generate for PasswordDatabase {
+    /// Each line is built from how many times the target should appear and where, so whether it
+    /// passes either policy is known without looking at the password again.
+    fn generate<R: Rng>(rng: &mut R, size: usize) -> Generated<Self> {
+        let mut input = String::new();
+        let mut by_count = 0;
+        let mut by_index = 0;
+
+        for _ in 0..size {
+            let len = rng.gen_range(1..=20);
+            let left = rng.gen_range(1..=len);
+            let right = rng.gen_range(left..=len);
+            let target = letter(rng);
+
+            let count = rng.gen_range(0..=len);
+            let mut positions = vec![false; len];
+            for i in index::sample(rng, len, count) {
+                positions[i] = true;
+            }
+
+            let password: String = positions
+                .iter()
+                .map(|&is_target| {
+                    if is_target {
+                        return target;
+                    }
+                    loop {
+                        let c = letter(rng);
+                        if c != target {
+                            break c;
+                        }
+                    }
+                })
+                .collect();
+
+            if count >= left && count <= right {
+                by_count += 1;
+            }
+            if positions[left - 1] != positions[right - 1] {
+                by_index += 1;
+            }
+
+            input.push_str(&format!("{}-{} {}: {}\n", left, right, target, password));
+        }
+
+        Generated {
+            input,
+            part_one: Some(by_count),
+            part_two: Some(by_index),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aoc::rand::rngs::StdRng;
+    use aoc::rand::SeedableRng;
+    use aoc::Solution;
+
+    #[test]
+    fn test_generated_answers() {
+        for seed in 0..20 {
+            let generated = PasswordDatabase::generate(&mut StdRng::seed_from_u64(seed), 200);
+            let db = PasswordDatabase::parse(&generated.input).unwrap();
+            assert_eq!(generated.part_one, db.part_one().unwrap());
+            assert_eq!(generated.part_two, db.part_two().unwrap());
+        }
+    }
+}
@@ -0,0 +1,489 @@
+use aoc::serde_json::{json, Value};
+use aoc::{Error, ParseError, Solution};
+use std::fmt;
+use std::ops::Range;
+use unicode_segmentation::UnicodeSegmentation;
+
+pub use mode::{Mode, Skipped};
+pub use policy::{ByCount, ByIndex, PasswordPolicy, Verdict, Violation, POLICIES};
+pub use rule::Rule;
+pub use stats::Stats;
+pub use unit::Unit;
+
+aoc::embed_input!();
+
+pub mod fixture;
+mod generate;
+mod mode;
+mod parse;
+pub mod policy;
+pub mod rule;
+pub mod stats;
+pub mod unit;
+
+macro_rules! regex {
+    ($re:literal $(,)?) => {{
+        static RE: once_cell::sync::OnceCell<regex::Regex> = once_cell::sync::OnceCell::new();
+        RE.get_or_init(|| regex::Regex::new($re).unwrap())
+    }};
+}
+
+/// One line of the database, borrowing its target, password and any rule from the line.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PasswordEntry<'a> {
+    pub left: usize,
+    pub right: usize,
+    /// A single grapheme cluster, which may be more than one char or byte.
+    pub target: &'a str,
+    pub password: &'a str,
+    /// What `left`, `right` and the target count are measured in.
+    pub unit: Unit,
+    /// The text of the line's own `Rule`, which only a parse sets.
+    rule: Option<&'a str>,
+}
+
+impl<'a> PasswordEntry<'a> {
+    /// Parses a line like `1-3 a: abcde` or `1-3 a where has 1+ digit: abc1`, without allocating
+    /// unless it's malformed or has a rule. Only a `PasswordDatabase` keeps the rule to check
+    /// entries against.
+    pub fn parse(line: &'a str) -> Result<Self, ParseError> {
+        parse::entry(line).map(|(entry, _)| entry)
+    }
+
+    /// The line's own rule, as written.
+    pub fn rule(&self) -> Option<&'a str> {
+        self.rule
+    }
+
+    /// Parses a line with a regex, the way it used to be done. It's kept to benchmark and fuzz
+    /// `parse` against.
+    pub fn parse_regex(line: &'a str) -> Result<Self, ParseError> {
+        let caps = regex!(r"(\d+)-(\d+)\s+(\S+?):\s+(\S+)")
+            .captures(line)
+            .ok_or_else(|| ParseError::new(line, 1, "expected a line like '1-3 a: abcde'"))?;
+
+        let position = |i: usize, name: &str| -> Result<usize, ParseError> {
+            let m = caps
+                .get(i)
+                .ok_or_else(|| ParseError::new(line, 1, format!("no {} value found", name)))?;
+            let pos = m.as_str().parse::<usize>().map_err(|e| {
+                ParseError::new(
+                    line,
+                    m.start() + 1,
+                    format!("failed to parse usize {}: {}", name, e),
+                )
+            })?;
+            if pos == 0 {
+                return Err(ParseError::new(
+                    line,
+                    m.start() + 1,
+                    format!("{} position must be at least 1", name),
+                ));
+            }
+            Ok(pos)
+        };
+
+        let left = position(1, "left")?;
+        let right = position(2, "right")?;
+
+        let target = caps
+            .get(3)
+            .ok_or_else(|| ParseError::new(line, 1, "no target char found"))?;
+        if target.as_str().graphemes(true).count() != 1 {
+            return Err(ParseError::new(
+                line,
+                target.start() + 1,
+                "target must be a single character",
+            ));
+        }
+
+        let password = caps
+            .get(4)
+            .ok_or_else(|| ParseError::new(line, 1, "no password found"))?;
+
+        Ok(PasswordEntry {
+            left,
+            right,
+            target: target.as_str(),
+            password: password.as_str(),
+            unit: Unit::default(),
+            rule: None,
+        })
+    }
+
+    pub fn target_count(&self) -> usize {
+        self.unit.count(self.password, self.target)
+    }
+
+    pub fn left_unit(&self) -> Option<&'a [u8]> {
+        self.unit.at(self.password, self.left)
+    }
+
+    pub fn right_unit(&self) -> Option<&'a [u8]> {
+        self.unit.at(self.password, self.right)
+    }
+
+    /// The first of `left` and `right` that's past the end of the password, if either is.
+    pub fn past_end(&self) -> Option<usize> {
+        let length = self.unit.length(self.password);
+        [self.left, self.right]
+            .iter()
+            .copied()
+            .find(|&pos| pos > length)
+    }
+}
+
+impl fmt::Display for PasswordEntry<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{} {}", self.left, self.right, self.target)?;
+        if let Some(rule) = self.rule {
+            write!(f, " where {}", rule)?;
+        }
+        write!(f, ": {}", self.password)
+    }
+}
+
+/// Where an entry's target, password and rule are in the database's text.
+struct Span {
+    line: usize,
+    left: usize,
+    right: usize,
+    target: Range<usize>,
+    password: Range<usize>,
+    /// The line's own rule, where it's written and parsed.
+    rule: Option<(Range<usize>, Rule)>,
+}
+
+impl Span {
+    /// Records where an entry parsed from line `line` of `text` is in it, along with its rule.
+    fn new(text: &str, line: usize, entry: &PasswordEntry, rule: Option<Rule>) -> Self {
+        let range = |part: &str| {
+            let start = part.as_ptr() as usize - text.as_ptr() as usize;
+            start..start + part.len()
+        };
+        Span {
+            line,
+            left: entry.left,
+            right: entry.right,
+            target: range(entry.target),
+            password: range(entry.password),
+            rule: entry.rule.map(range).zip(rule),
+        }
+    }
+
+    /// Checks the span's entry against the policy and the line's own rule, if it has one,
+    /// failing with both violations when both fail.
+    fn check(&self, entry: &PasswordEntry, policy: &dyn PasswordPolicy) -> Verdict {
+        let verdict = policy.check(entry);
+        let own = match &self.rule {
+            Some((_, rule)) => rule.check(entry),
+            None => return verdict,
+        };
+        match (verdict, own) {
+            (Verdict::Valid, own) => own,
+            (verdict, Verdict::Valid) => verdict,
+            (Verdict::Invalid(a), Verdict::Invalid(b)) => {
+                Verdict::Invalid(Violation::All(vec![a, b]))
+            }
+        }
+    }
+}
+
+/// The password database: one policy and password per line.
+///
+/// It keeps a single copy of its input, which every entry borrows from.
+pub struct PasswordDatabase {
+    text: String,
+    spans: Vec<Span>,
+    unit: Unit,
+    skipped: Skipped,
+}
+
+impl PasswordDatabase {
+    /// Every entry, in line order.
+    pub fn entries(&self) -> impl ExactSizeIterator<Item = PasswordEntry<'_>> {
+        self.lines().map(|(_, entry)| entry)
+    }
+
+    /// Every entry with the number of the line it's on, which a lenient parse may have skipped
+    /// some of.
+    pub fn lines(&self) -> impl ExactSizeIterator<Item = (usize, PasswordEntry<'_>)> {
+        self.spans.iter().map(move |span| {
+            let entry = PasswordEntry {
+                left: span.left,
+                right: span.right,
+                target: &self.text[span.target.clone()],
+                password: &self.text[span.password.clone()],
+                unit: self.unit,
+                rule: span.rule.as_ref().map(|(rule, _)| &self.text[rule.clone()]),
+            };
+            (span.line, entry)
+        })
+    }
+
+    /// The lines a lenient parse left out, which is none of them for any other parse.
+    pub fn skipped(&self) -> &Skipped {
+        &self.skipped
+    }
+
+    /// Measures every entry's positions and counts in `unit` from now on.
+    pub fn set_unit(&mut self, unit: Unit) {
+        self.unit = unit;
+    }
+
+    /// Every entry with the number of the line it's on and its verdict under each policy, which
+    /// its line's own rule has to pass as well.
+    pub fn verdicts<'a>(
+        &'a self,
+        policies: &'a [&'a dyn PasswordPolicy],
+    ) -> impl Iterator<Item = (usize, PasswordEntry<'a>, Vec<Verdict>)> + 'a {
+        self.spans
+            .iter()
+            .zip(self.lines())
+            .map(move |(span, (line, entry))| {
+                let verdicts = policies.iter().map(|p| span.check(&entry, *p)).collect();
+                (line, entry, verdicts)
+            })
+    }
+
+    /// How many passwords follow the policy and their line's own rule.
+    pub fn count_valid(&self, policy: &dyn PasswordPolicy) -> usize {
+        self.spans
+            .iter()
+            .zip(self.entries())
+            .filter(|(span, entry)| span.check(entry, policy).is_valid())
+            .count()
+    }
+
+    /// How many passwords follow one of the puzzle's policies, whatever their lines' own rules say.
+    fn count_puzzle(&self, policy: &dyn PasswordPolicy) -> usize {
+        self.entries()
+            .filter(|entry| policy.check(entry).is_valid())
+            .count()
+    }
+
+    /// Every entry that breaks the policy, with its line number and how it breaks it.
+    pub fn violations<'a>(
+        &'a self,
+        policy: &'a dyn PasswordPolicy,
+    ) -> impl Iterator<Item = (usize, PasswordEntry<'a>, Violation)> + 'a {
+        self.spans
+            .iter()
+            .zip(self.lines())
+            .filter_map(
+                move |(span, (line, entry))| match span.check(&entry, policy) {
+                    Verdict::Valid => None,
+                    Verdict::Invalid(violation) => Some((line, entry, violation)),
+                },
+            )
+    }
+}
+
+/// The puzzle's answers go by its two policies alone: a line's own rule only counts when the
+/// line's checked against a policy of your choosing (`count_valid`, `violations`, `verdicts`).
+impl Solution for PasswordDatabase {
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> Result<Self, Error> {
+        let text = String::from(input);
+        let mut number = 0;
+        let spans = aoc::parse_lines(&text, |line| {
+            number += 1;
+            parse::entry(line).map(|(entry, rule)| Span::new(&text, number, &entry, rule))
+        })?;
+
+        Ok(Self {
+            text,
+            spans,
+            unit: Unit::default(),
+            skipped: Skipped::default(),
+        })
+    }
+
+    fn part_one(&self) -> Result<Option<usize>, Error> {
+        Ok(Some(self.count_puzzle(&ByCount)))
+    }
+
+    fn part_two(&self) -> Result<Option<usize>, Error> {
+        Ok(Some(self.count_puzzle(&ByIndex)))
+    }
+
+    fn details(&self) -> Value {
+        let mut details = json!({ "entries": self.spans.len() });
+        if !self.skipped.is_empty() {
+            details["skipped"] = json!({
+                "malformed": self.skipped.malformed.iter().map(|e| e.line).collect::<Vec<_>>(),
+                "past_end": self.skipped.past_end,
+            });
+        }
+        details
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use proptest::prelude::*;
+
+    /// Passwords mixing ASCII, accented letters and emoji, each a single char.
+    fn entry() -> impl Strategy<Value = (usize, usize, String, String)> {
+        (1usize..25, 0usize..25, "[a-cA-Cé😀]", "[a-cA-Cé😀]{1,25}")
+            .prop_map(|(left, extra, target, password)| (left, left + extra, target, password))
+    }
+
+    #[test]
+    fn test_find_policy() {
+        assert_eq!("index", policy::find("index").unwrap().name());
+        assert!(policy::find("length").is_none());
+    }
+
+    #[test]
+    fn test_sample() {
+        let db = PasswordDatabase::parse("1-3 a: abcde\n1-3 b: cdefg\n2-9 c: ccccccccc").unwrap();
+        assert_eq!(Some(2), db.part_one().unwrap());
+        assert_eq!(Some(1), db.part_two().unwrap());
+    }
+
+    #[test]
+    fn test_violations() {
+        let db = PasswordDatabase::parse("1-3 a: abcde\n1-3 b: cdefg\n2-9 c: ccccccccc").unwrap();
+
+        let found: Vec<(usize, String)> = db
+            .violations(&ByCount)
+            .map(|(line, _, v)| (line, v.to_string()))
+            .collect();
+        assert_eq!(
+            vec![(2, String::from("'b' appears 0 times, allowed 1-3"))],
+            found
+        );
+
+        let found: Vec<(usize, String)> = db
+            .violations(&ByIndex)
+            .map(|(line, _, v)| (line, v.to_string()))
+            .collect();
+        assert_eq!(
+            vec![
+                (
+                    2,
+                    String::from("neither position 1 ('c') nor 3 ('e') contains 'b'")
+                ),
+                (3, String::from("positions 2 and 9 both contain 'c'")),
+            ],
+            found
+        );
+
+        let entry = PasswordEntry::parse("2-9 a: abcaa").unwrap();
+        assert_eq!(
+            "neither position 2 ('b') nor 9 (past the end) contains 'a'",
+            ByIndex.check(&entry).violation().unwrap().to_string()
+        );
+    }
+
+    #[test]
+    fn test_own_rules() {
+        // each line's own rule has to pass too, whichever policy the line's checked against
+        let db = PasswordDatabase::parse(
+            "1-3 a where has 1+ digit: abcde\n\
+             1-3 a where has 1+ digit: a1cde\n\
+             2-9 c where count: ccccccccc\n\
+             1-3 b: cdefg",
+        )
+        .unwrap();
+        assert_eq!(2, db.count_valid(&ByCount));
+        assert_eq!(1, db.count_valid(&ByIndex));
+        // but not for the puzzle's answers, which line 1 passes both halves of
+        assert_eq!(Some(3), db.part_one().unwrap());
+        assert_eq!(Some(2), db.part_two().unwrap());
+
+        let found: Vec<(usize, String)> = db
+            .violations(&ByIndex)
+            .map(|(line, _, v)| (line, v.to_string()))
+            .collect();
+        assert_eq!(
+            vec![
+                (1, String::from("0 of digit, needs 1+")),
+                (3, String::from("positions 2 and 9 both contain 'c'")),
+                (
+                    4,
+                    String::from("neither position 1 ('c') nor 3 ('e') contains 'b'")
+                ),
+            ],
+            found
+        );
+
+        let db = PasswordDatabase::parse("2-3 b where has 1+ digit: abcde").unwrap();
+        let (_, entry, violation) = db.violations(&ByCount).next().unwrap();
+        assert_eq!(
+            "'b' appears 1 time, allowed 2-3; 0 of digit, needs 1+",
+            violation.to_string()
+        );
+        assert_eq!("2-3 b where has 1+ digit: abcde", entry.to_string());
+    }
+
+    #[test]
+    fn test_unicode() {
+        // "é" as one char, then as "e" with a combining acute accent: there's no normalizing, so
+        // the two never match each other, and the second is only one unit in graphemes
+        let mut db =
+            PasswordDatabase::parse("1-2 é: éte\u{301}\n1-3 e\u{301}: e\u{301}té").unwrap();
+        db.set_unit(Unit::Chars);
+        assert_eq!(1, db.count_valid(&ByCount));
+        assert_eq!(1, db.count_valid(&ByIndex));
+        db.set_unit(Unit::Graphemes);
+        assert_eq!(2, db.count_valid(&ByCount));
+        assert_eq!(2, db.count_valid(&ByIndex));
+
+        let mut entry = PasswordEntry::parse("2-4 👍🏽: a👍🏽b👍🏽").unwrap();
+        assert_eq!(0, entry.target_count());
+        entry.unit = Unit::Graphemes;
+        assert_eq!(2, entry.target_count());
+        assert_eq!(
+            "positions 2 and 4 both contain '👍🏽'",
+            ByIndex.check(&entry).violation().unwrap().to_string()
+        );
+        entry.unit = Unit::Bytes;
+        assert_eq!(
+            "neither position 2 ('\\xf0') nor 4 ('\\x91') contains '👍🏽'",
+            ByIndex.check(&entry).violation().unwrap().to_string()
+        );
+
+        let entry = PasswordEntry::parse("1-3 Ü: ÜBER").unwrap();
+        assert!(ByCount.check(&entry).is_valid());
+        assert!(ByIndex.check(&entry).is_valid());
+    }
+
+    #[test]
+    fn test_target_not_single() {
+        let e = PasswordEntry::parse("1-3 ab: abcde").unwrap_err();
+        assert_eq!(5, e.column);
+        assert!(PasswordEntry::parse("1-3 a: abcde").is_ok());
+    }
+
+    proptest! {
+        #[test]
+        fn prop_parse_round_trip((left, right, target, password) in entry()) {
+            let line = format!("{}-{} {}: {}", left, right, target, password);
+            let entry = PasswordEntry::parse(&line).unwrap();
+            prop_assert_eq!(&line, &entry.to_string());
+            prop_assert_eq!(
+                (left, right, target.as_str(), password.as_str()),
+                (entry.left, entry.right, entry.target, entry.password)
+            );
+        }
+
+        #[test]
+        fn prop_by_count((left, right, target, password) in entry()) {
+            let entry = PasswordEntry { left, right, target: &target, password: &password, unit: Unit::Chars, rule: None };
+            let count = entry.password.chars().filter(|c| c.to_string() == entry.target).count();
+            prop_assert_eq!(left <= count && count <= right, ByCount.check(&entry).is_valid());
+        }
+
+        #[test]
+        fn prop_by_index((left, right, target, password) in entry()) {
+            let entry = PasswordEntry { left, right, target: &target, password: &password, unit: Unit::Chars, rule: None };
+            let at = |pos: usize| entry.password.chars().nth(pos - 1).map(String::from) == Some(target.clone());
+            prop_assert_eq!(at(left) != at(right), ByIndex.check(&entry).is_valid());
+        }
+    }
+}
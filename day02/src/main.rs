@@ -1,139 +1,173 @@
-use std::env;
-use std::fs;
-use std::io;
-use std::io::BufRead;
-use std::str::FromStr;
-
-macro_rules! regex {
-    ($re:literal $(,)?) => {{
-        static RE: once_cell::sync::OnceCell<regex::Regex> = once_cell::sync::OnceCell::new();
-        RE.get_or_init(|| regex::Regex::new($re).unwrap())
-    }};
-}
-
-#[derive(Debug)]
-struct PasswordEntry {
-    left: usize,
-    right: usize,
-    target: char,
-    password: String,
-}
-
-impl PasswordEntry {
-    fn target_count(&self) -> usize {
-        self.password.matches(self.target).count()
-    }
-
-    fn left_char(&self) -> Option<char> {
-        self.password.chars().nth(self.left - 1)
-    }
-
-    fn right_char(&self) -> Option<char> {
-        self.password.chars().nth(self.right - 1)
-    }
-}
-
-impl FromStr for PasswordEntry {
-    type Err = String;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let caps = regex!(r"(\d+)-(\d+)\s+([a-z]):\s+([a-z]+)")
-            .captures(s)
-            .ok_or_else(|| format!("regex failed to find captures any line: '{}'", s))?;
-
-        if caps.len() == 0 {
-            return Err(format!("not enough captures found in line: '{}'", s));
+use aoc::rand::rngs::StdRng;
+use aoc::rand::SeedableRng;
+use aoc::{Args, Error, Solution};
+use day02::fixture::{self, Flaw, Outcome};
+use day02::{policy, stats, ByCount, Mode, PasswordDatabase, PasswordPolicy, Rule, Stats, Unit};
+use std::io::{self, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn main() {
+    aoc::main(|| {
+        let mut args = Args::from_env();
+        // a composite rule to check, e.g. `--rule 'count and has 1+ digit'`
+        let rule = match args.value::<String>("--rule")? {
+            Some(rule) => Some(rule.parse::<Rule>().map_err(|e| Error::Parse(vec![e]))?),
+            None => None,
+        };
+        // the policies to count passwords against, e.g. `--policy count,index`
+        let mut policies: Option<Vec<&dyn PasswordPolicy>> =
+            match args.value::<String>("--policy")? {
+                Some(names) => Some(policies(&names)?),
+                None => None,
+            };
+        let named = policies.is_some();
+        if let Some(rule) = rule.as_ref() {
+            policies.get_or_insert_with(Vec::new).push(rule);
+        }
+        // print every line that fails a policy, and why, rather than counting them
+        let report = args.flag("--report");
+        // print pass rates and how targets, lengths and positions are spread instead
+        let show_stats = args.flag("--stats");
+        // print every entry as CSV, with a column for each policy's verdict
+        let csv = args.flag("--csv");
+        // what positions and counts are measured in: bytes, chars or graphemes
+        let unit = args.value::<Unit>("--unit")?.unwrap_or_default();
+        // stop at the first line that's malformed or points past its password, or skip them all
+        let mode = args.value::<Mode>("--mode")?;
+        // print this many random lines passing the policy instead, or failing it with `--violate`
+        let generate = args.value::<usize>("--generate")?;
+        // how a generated line should fail, and the seed to generate from
+        let violate = args.value::<Flaw>("--violate")?;
+        let seed = args.value::<u64>("--seed")?;
+        if generate.is_none() && (violate.is_some() || seed.is_some()) {
+            return Err(invalid_input("--violate/--seed need --generate"));
+        }
+        args.reject_with_json(&[
+            ("--policy", named),
+            ("--rule", rule.is_some()),
+            ("--report", report),
+            ("--stats", show_stats),
+            ("--csv", csv),
+            ("--generate", generate.is_some()),
+        ])?;
+
+        if let Some(count) = generate {
+            let policy = match policies.as_deref() {
+                None => &ByCount,
+                Some(&[policy]) => policy,
+                Some(_) => return Err(invalid_input("--generate takes a single policy")),
+            };
+            let outcome = match violate {
+                Some(flaw) => Outcome::Fail(flaw),
+                None => Outcome::Pass,
+            };
+            let seed = seed.unwrap_or_else(|| {
+                SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map_or(0, |d| d.as_nanos() as u64)
+            });
+            eprintln!("seed: {}", seed);
+            return aoc::ignore_broken_pipe(print_fixtures(policy, outcome, unit, seed, count));
         }
 
-        let left = caps
-            .get(1)
-            .ok_or_else(|| format!("no left value found in line: '{}'", s))?
-            .as_str()
-            .parse::<usize>()
-            .map_err(|e| {
-                format!(
-                    "failed to parse usize left from line: '{}'. error: {}",
-                    s, e
-                )
-            })?;
-
-        let right = caps
-            .get(2)
-            .ok_or_else(|| format!("no right value found in line: '{}'", s))?
-            .as_str()
-            .parse::<usize>()
-            .map_err(|e| {
-                format!(
-                    "failed to parse usize right from line: '{}'. error: {}",
-                    s, e
-                )
-            })?;
-
-        let target = caps
-            .get(3)
-            .ok_or_else(|| format!("no target char found in line: '{}'", s))?
-            .as_str()
-            .chars()
-            .next()
-            .ok_or_else(|| format!("no chars in target string found for line: '{}'", s))?;
-
-        let password = caps
-            .get(4)
-            .ok_or_else(|| format!("no password found in line: '{}", s))?
-            .as_str()
-            .to_string();
-
-        Ok(PasswordEntry {
-            left,
-            right,
-            target,
-            password,
+        let print = |db: &PasswordDatabase| -> Result<(), Error> {
+            let listed = policies.as_deref().unwrap_or(day02::POLICIES);
+            let written = match policies.as_ref() {
+                _ if report => print_report(db, listed),
+                _ if show_stats => write!(io::stdout().lock(), "{}", Stats::new(db, listed)),
+                _ if csv => stats::write_csv(&mut io::stdout().lock(), db, listed),
+                Some(policies) => {
+                    for policy in policies {
+                        println!("{}: {}", policy.name(), db.count_valid(*policy));
+                    }
+                    Ok(())
+                }
+                None => {
+                    println!("{}", aoc::answer(db.part_one()?));
+                    println!("{}", aoc::answer(db.part_two()?));
+                    Ok(())
+                }
+            };
+            aoc::ignore_broken_pipe(written)
+        };
+
+        let parse = |input: &str| match mode {
+            Some(mode) => PasswordDatabase::parse_with(input, unit, mode),
+            None => PasswordDatabase::parse(input).map(|mut db| {
+                db.set_unit(unit);
+                db
+            }),
+        };
+        aoc::for_each_input_with(args, day02::INPUT, parse, |db: PasswordDatabase| {
+            print(&db)?;
+            if !db.skipped().is_empty() {
+                eprintln!("{}", db.skipped());
+            }
+            Ok(())
         })
-    }
+    })
 }
 
-fn by_count(entry: &PasswordEntry) -> Option<()> {
-    let count = entry.target_count();
-    if count > entry.right || count < entry.left {
-        return None;
+/// Prints every line that breaks one of the policies, once per policy it breaks.
+fn print_report(db: &PasswordDatabase, policies: &[&dyn PasswordPolicy]) -> io::Result<()> {
+    let mut out = io::stdout().lock();
+    for (line, entry, verdicts) in db.verdicts(policies) {
+        for (policy, verdict) in policies.iter().zip(verdicts) {
+            if let Some(violation) = verdict.violation() {
+                writeln!(
+                    out,
+                    "line {}: {}: {} ({})",
+                    line,
+                    policy.name(),
+                    violation,
+                    entry
+                )?;
+            }
+        }
     }
-    Some(())
+    Ok(())
 }
 
-fn by_index(entry: &PasswordEntry) -> Option<()> {
-    let l = entry.left_char().expect("invalid left index");
-    let r = entry.right_char().expect("invalid right index");
-    if l != r && (l == entry.target || r == entry.target) {
-        return Some(());
+/// Prints `count` random lines that come out of the policy as asked.
+fn print_fixtures(
+    policy: &dyn PasswordPolicy,
+    outcome: Outcome,
+    unit: Unit,
+    seed: u64,
+    count: usize,
+) -> io::Result<()> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut out = io::stdout().lock();
+    for _ in 0..count {
+        let entry = fixture::fixture(&mut rng, policy, outcome, unit).ok_or_else(|| {
+            io::Error::other(format!(
+                "couldn't find a password that {} {} in {} tries",
+                outcome,
+                policy.name(),
+                fixture::TRIES
+            ))
+        })?;
+        writeln!(out, "{}", entry)?;
     }
-    None
+    Ok(())
 }
 
-fn main() -> io::Result<()> {
-    let args: Vec<String> = env::args().collect();
-    if args.len() != 2 {
-        panic!("missing filename arg")
-    }
-
-    let f = fs::File::open(&args[1])?;
-    let reader = io::BufReader::new(f);
-
-    let results = reader
-        .lines()
-        .fold((0 as u64, 0 as u64), |acc, l| -> (u64, u64) {
-            let entry = l
-                .expect("failed to read line")
-                .parse::<PasswordEntry>()
-                .unwrap();
-
-            (
-                acc.0 + by_count(&entry).map_or(0, |_| 1),
-                acc.1 + by_index(&entry).map_or(0, |_| 1),
-            )
-        });
-
-    println!("{}", results.0);
-    println!("{}", results.1);
+fn invalid_input(message: impl Into<String>) -> Error {
+    Error::Io(io::Error::new(io::ErrorKind::InvalidInput, message.into()))
+}
 
-    Ok(())
+fn policies(names: &str) -> Result<Vec<&'static dyn PasswordPolicy>, Error> {
+    names
+        .split(',')
+        .map(|name| {
+            policy::find(name).ok_or_else(|| {
+                let known: Vec<&str> = day02::POLICIES.iter().map(|p| p.name()).collect();
+                invalid_input(format!(
+                    "unknown policy: '{}' (expected {})",
+                    name,
+                    known.join(", ")
+                ))
+            })
+        })
+        .collect()
 }
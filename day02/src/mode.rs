@@ -0,0 +1,224 @@
+use crate::{parse, PasswordDatabase, Span, Unit};
+use aoc::{Error, ParseError};
+use std::fmt;
+use std::str::FromStr;
+
+/// How `PasswordDatabase::parse_with` treats a line it can't use: one that's malformed, or
+/// whose positions go past the end of its password.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Mode {
+    /// Fail on the first such line.
+    Strict,
+    /// Skip every such line, keeping track of which they were.
+    Lenient,
+}
+
+impl FromStr for Mode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "strict" => Ok(Mode::Strict),
+            "lenient" => Ok(Mode::Lenient),
+            _ => Err(String::from("expected strict or lenient")),
+        }
+    }
+}
+
+/// The lines a lenient parse left out.
+#[derive(Debug, Default)]
+pub struct Skipped {
+    pub malformed: Vec<ParseError>,
+    /// The line numbers of entries with a position past the end of their password.
+    pub past_end: Vec<usize>,
+}
+
+impl Skipped {
+    pub fn is_empty(&self) -> bool {
+        self.malformed.is_empty() && self.past_end.is_empty()
+    }
+}
+
+/// How many lines to list by number before leaving the rest out.
+const LISTED: usize = 5;
+
+fn lines(lines: impl ExactSizeIterator<Item = usize>) -> String {
+    let more = lines.len().saturating_sub(LISTED);
+    let mut listed: Vec<String> = lines.take(LISTED).map(|l| l.to_string()).collect();
+    if more > 0 {
+        listed.push(format!("{} more", more));
+    }
+    listed.join(", ")
+}
+
+impl fmt::Display for Skipped {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "skipped no lines");
+        }
+
+        let malformed = self.malformed.len();
+        let past_end = self.past_end.len();
+        write!(f, "skipped {} line", malformed + past_end)?;
+        if malformed + past_end != 1 {
+            write!(f, "s")?;
+        }
+        if malformed > 0 {
+            let at = lines(self.malformed.iter().map(|e| e.line));
+            write!(f, "\n  {} malformed (line {})", malformed, at)?;
+        }
+        if past_end > 0 {
+            let at = lines(self.past_end.iter().copied());
+            write!(
+                f,
+                "\n  {} with a position past the end of the password (line {})",
+                past_end, at
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl PasswordDatabase {
+    /// Parses the input measuring positions in `unit`, treating lines it can't use as `mode`
+    /// says. A strict parse never skips anything; a lenient one keeps what it skipped in
+    /// `skipped`.
+    pub fn parse_with(input: &str, unit: Unit, mode: Mode) -> Result<Self, Error> {
+        let text = String::from(input);
+        let mut spans = Vec::new();
+        let mut skipped = Skipped::default();
+
+        for (i, line) in text.lines().enumerate() {
+            let (mut entry, rule) = match parse::entry(line) {
+                Ok(parsed) => parsed,
+                Err(e) if mode == Mode::Lenient => {
+                    skipped.malformed.push(e.at_line(i + 1));
+                    continue;
+                }
+                Err(e) => return Err(Error::Parse(vec![e.at_line(i + 1)])),
+            };
+            entry.unit = unit;
+
+            if let Some(pos) = entry.past_end() {
+                if mode == Mode::Lenient {
+                    skipped.past_end.push(i + 1);
+                    continue;
+                }
+                // the left position starts the line and the right one follows the '-'
+                let column = if pos == entry.left {
+                    1
+                } else {
+                    line.find('-').map_or(1, |dash| dash + 2)
+                };
+                let e = ParseError::new(
+                    line,
+                    column,
+                    format!("position {} is past the end of the password", pos),
+                );
+                return Err(Error::Parse(vec![e.at_line(i + 1)]));
+            }
+
+            spans.push(Span::new(&text, i + 1, &entry, rule));
+        }
+
+        Ok(Self {
+            text,
+            spans,
+            unit,
+            skipped,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const INPUT: &str = "1-3 a: abcde\n1-3 b cdefg\n2-9 c: ccccccccc\n1-2 é: é\nx\n";
+
+    #[test]
+    fn test_strict() {
+        let e = match PasswordDatabase::parse_with(INPUT, Unit::Chars, Mode::Strict) {
+            Err(Error::Parse(errors)) => errors,
+            _ => panic!("expected a parse error"),
+        };
+        assert_eq!(1, e.len());
+        assert_eq!((2, 6), (e[0].line, e[0].column));
+
+        let e = match PasswordDatabase::parse_with(
+            "1-3 a: abcde\n1-13 é: éé",
+            Unit::Bytes,
+            Mode::Strict,
+        ) {
+            Err(Error::Parse(errors)) => errors,
+            _ => panic!("expected a parse error"),
+        };
+        assert_eq!((2, 3), (e[0].line, e[0].column));
+        assert!(e[0].message.contains("position 13"));
+
+        let db = PasswordDatabase::parse_with(
+            "1-3 a: abcde\n2-9 c: ccccccccc",
+            Unit::Chars,
+            Mode::Strict,
+        )
+        .unwrap();
+        assert_eq!(2, db.entries().len());
+        assert!(db.skipped().is_empty());
+    }
+
+    #[test]
+    fn test_lenient() {
+        let db = PasswordDatabase::parse_with(INPUT, Unit::Chars, Mode::Lenient).unwrap();
+        let skipped = db.skipped();
+        assert_eq!(2, db.entries().len());
+        let lines: Vec<usize> = skipped.malformed.iter().map(|e| e.line).collect();
+        assert_eq!(vec![2, 5], lines);
+        assert_eq!(vec![4], skipped.past_end);
+        let lines: Vec<usize> = db.lines().map(|(line, _)| line).collect();
+        assert_eq!(vec![1, 3], lines);
+        assert_eq!(
+            "skipped 3 lines\n  2 malformed (line 2, 5)\n  1 with a position past the end of the password (line 4)",
+            skipped.to_string()
+        );
+
+        // "é" is two bytes, so both positions fit
+        let db = PasswordDatabase::parse_with(INPUT, Unit::Bytes, Mode::Lenient).unwrap();
+        assert_eq!(3, db.entries().len());
+        assert!(db.skipped().past_end.is_empty());
+    }
+
+    #[test]
+    fn test_json() {
+        // what `--mode lenient --format json` prints for each input
+        let parts = [aoc::Part::One, aoc::Part::Two];
+        let doc = aoc::solve_with(INPUT, &parts, |input| {
+            PasswordDatabase::parse_with(input, Unit::Chars, Mode::Lenient)
+        })
+        .unwrap()
+        .to_json();
+        assert_eq!(2, doc["answers"]["part_one"]);
+        assert_eq!(1, doc["answers"]["part_two"]);
+        assert_eq!(2, doc["details"]["entries"]);
+        assert_eq!(
+            aoc::serde_json::json!({ "malformed": [2, 5], "past_end": [4] }),
+            doc["details"]["skipped"]
+        );
+
+        let strict = aoc::solve_with(INPUT, &parts, |input| {
+            PasswordDatabase::parse_with(input, Unit::Chars, Mode::Strict)
+        });
+        assert!(matches!(strict, Err(Error::Parse(_))));
+    }
+
+    #[test]
+    fn test_summary() {
+        let skipped = Skipped {
+            malformed: Vec::new(),
+            past_end: (1..=8).collect(),
+        };
+        assert_eq!(
+            "skipped 8 lines\n  8 with a position past the end of the password (line 1, 2, 3, 4, 5, 3 more)",
+            skipped.to_string()
+        );
+    }
+}
@@ -0,0 +1,250 @@
+use crate::{PasswordEntry, Rule, Unit};
+use aoc::ParseError;
+use unicode_segmentation::UnicodeSegmentation;
+
+fn error(line: &str, at: usize, message: &'static str) -> ParseError {
+    ParseError::new(line, at + 1, message)
+}
+
+/// Reads the position starting at byte `i`, returning it and where it stops.
+fn position(line: &str, i: usize) -> Result<(usize, usize), ParseError> {
+    let digits = &line.as_bytes()[i..];
+    let len = digits.iter().take_while(|b| b.is_ascii_digit()).count();
+    if len == 0 {
+        return Err(error(line, i, "expected a position"));
+    }
+
+    let mut n: usize = 0;
+    for &digit in &digits[..len] {
+        n = n
+            .checked_mul(10)
+            .and_then(|n| n.checked_add(usize::from(digit - b'0')))
+            .ok_or_else(|| error(line, i, "position is too large"))?;
+    }
+    if n == 0 {
+        return Err(error(line, i, "position must be at least 1"));
+    }
+    Ok((n, i + len))
+}
+
+/// How many bytes of whitespace start at byte `i`.
+fn whitespace(line: &str, i: usize) -> usize {
+    line[i..]
+        .char_indices()
+        .find(|&(_, c)| !c.is_whitespace())
+        .map_or(line.len() - i, |(len, _)| len)
+}
+
+/// Whether the line's own rule starts after the whitespace at byte `i`.
+fn rule_follows(line: &str, i: usize) -> bool {
+    let rest = &line[i + whitespace(line, i)..];
+    rest.get(..WHERE.len())
+        .is_some_and(|w| w.eq_ignore_ascii_case(WHERE))
+        && rest[WHERE.len()..].starts_with(char::is_whitespace)
+}
+
+const WHERE: &str = "where";
+
+/// Reads the line's own rule, which starts with `where` after the whitespace at byte `i`, and
+/// runs up to the last ':' that only has the password after it. Returns the rule as written,
+/// parsed, and where that ':' is.
+fn rule(line: &str, i: usize) -> Result<(&str, Rule, usize), ParseError> {
+    let start = i + whitespace(line, i) + WHERE.len();
+    let colon = line[start..]
+        .rmatch_indices(':')
+        .map(|(n, _)| start + n)
+        .find(|&n| {
+            let rest = &line[n + 1..];
+            rest.starts_with(char::is_whitespace) && rest.split_whitespace().count() == 1
+        })
+        .ok_or_else(|| {
+            error(
+                line,
+                line.len(),
+                "expected ':' and a password after the rule",
+            )
+        })?;
+
+    let text = &line[start..colon];
+    let rule = text
+        .parse::<Rule>()
+        .map_err(|e| ParseError::new(line, start + e.column, e.message))?;
+    Ok((text.trim(), rule, colon))
+}
+
+/// Parses a line like `1-3 a: abcde` by hand, borrowing the target and password from it, and
+/// only allocating for an error. An error's column is the byte (counting from 1) where the
+/// line stopped making sense.
+///
+/// The target can be followed by a rule of the line's own, as in `1-3 a where has 1+ digit:
+/// abc1`, which comes back parsed alongside the entry. Without one it accepts what the regex
+/// `(\d+)-(\d+)\s+(\S+?):\s+(\S+)` does, except that the match has to be the whole line, give or
+/// take whitespace at the end.
+pub(crate) fn entry(line: &str) -> Result<(PasswordEntry<'_>, Option<Rule>), ParseError> {
+    let bytes = line.as_bytes();
+
+    let (left, i) = position(line, 0)?;
+    if bytes.get(i) != Some(&b'-') {
+        return Err(error(line, i, "expected '-' between the positions"));
+    }
+    let (right, i) = position(line, i + 1)?;
+
+    let gap = whitespace(line, i);
+    if gap == 0 {
+        return Err(error(line, i, "expected a space after the positions"));
+    }
+
+    // the target runs up to the first ':' that has whitespace after it, so it can be a ':'
+    let start = i + gap;
+    let mut chars = line[start..].char_indices().peekable();
+    let mut colon = None;
+    let end = loop {
+        match chars.next() {
+            Some((n, ':')) if n > 0 => {
+                if chars.peek().is_some_and(|&(_, c)| c.is_whitespace()) {
+                    break start + n;
+                }
+                colon = colon.or(Some(start + n));
+            }
+            Some((_, c)) if !c.is_whitespace() => {}
+            Some((n, _)) if n > 0 && rule_follows(line, start + n) => break start + n,
+            stop => {
+                return Err(match colon {
+                    Some(colon) => error(line, colon + 1, "expected a space after ':'"),
+                    None => {
+                        let at = stop.map_or(line.len(), |(n, _)| start + n);
+                        error(line, at, "expected ':' after the target")
+                    }
+                });
+            }
+        }
+    };
+    let target = &line[start..end];
+    if target.len() > 1 && target.graphemes(true).nth(1).is_some() {
+        return Err(error(line, start, "target must be a single character"));
+    }
+
+    let (rule, colon) = match line.as_bytes()[end] {
+        b':' => (None, end),
+        _ => {
+            let (text, rule, colon) = rule(line, end)?;
+            (Some((text, rule)), colon)
+        }
+    };
+
+    let start = colon + 1 + whitespace(line, colon + 1);
+    let len = line[start..]
+        .find(char::is_whitespace)
+        .unwrap_or(line.len() - start);
+    if len == 0 {
+        return Err(error(line, start, "expected a password"));
+    }
+    let end = start + len;
+    if end + whitespace(line, end) != line.len() {
+        return Err(error(
+            line,
+            end + whitespace(line, end),
+            "unexpected text after the password",
+        ));
+    }
+
+    let (text, rule) = rule.unzip();
+    let entry = PasswordEntry {
+        left,
+        right,
+        target,
+        password: &line[start..end],
+        unit: Unit::default(),
+        rule: text,
+    };
+    Ok((entry, rule))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_entry() {
+        let e = entry("1-3 a: abcde").unwrap().0;
+        assert_eq!(
+            (1, 3, "a", "abcde"),
+            (e.left, e.right, e.target, e.password)
+        );
+
+        let e = entry("12-345\t:: a:b:c  ").unwrap().0;
+        assert_eq!(
+            (12, 345, ":", "a:b:c"),
+            (e.left, e.right, e.target, e.password)
+        );
+
+        let e = entry("2-4 👍🏽: a👍🏽b👍🏽").unwrap().0;
+        assert_eq!(("👍🏽", "a👍🏽b👍🏽"), (e.target, e.password));
+    }
+
+    #[test]
+    fn test_rule() {
+        let (e, rule) = entry("1-3 a where HAS 1+ digit: abc1").unwrap();
+        assert_eq!(
+            (1, 3, "a", Some("HAS 1+ digit"), "abc1"),
+            (e.left, e.right, e.target, e.rule(), e.password)
+        );
+        assert_eq!("has 1+ digit", rule.unwrap().to_string());
+
+        // a quoted ": " doesn't end the rule, and neither does a password ending in ':'
+        let e = entry("1-3 : WHERE forbid \": \": a:b:  ").unwrap().0;
+        assert_eq!(
+            (":", Some("forbid \": \""), "a:b:"),
+            (e.target, e.rule(), e.password)
+        );
+
+        assert_eq!(None, entry("1-3 a: where").unwrap().0.rule());
+
+        let deep = format!(
+            "1-3 a where {}count{}: abc",
+            "(".repeat(20_000),
+            ")".repeat(20_000)
+        );
+        let e = entry(&deep).map(|_| ()).unwrap_err();
+        assert_eq!("rule is nested too deeply", e.message);
+        // the 257th '('
+        assert_eq!(13 + 256, e.column);
+    }
+
+    #[test]
+    fn test_columns() {
+        let column = |line: &str| entry(line).map(|_| ()).unwrap_err().column;
+        assert_eq!(1, column(""));
+        assert_eq!(1, column("a-3 a: abcde"));
+        assert_eq!(1, column("0-3 a: abcde"));
+        assert_eq!(2, column("1 3 a: abcde"));
+        assert_eq!(3, column("1-x a: abcde"));
+        assert_eq!(4, column("1-3a: abcde"));
+        assert_eq!(6, column("1-3 a abcde"));
+        assert_eq!(7, column("1-3 a:abcde"));
+        assert_eq!(5, column("1-3 ab: abcde"));
+        assert_eq!(8, column("1-3 a: "));
+        assert_eq!(14, column("1-3 a: abcde x"));
+        // bytes, not chars: "é" takes two
+        assert_eq!(9, column("1-3 é: "));
+        assert_eq!(8, column("1-3 é:é"));
+        assert_eq!(1, column("99999999999999999999999-1 a: b"));
+        // a rule's errors point into it
+        assert_eq!(20, column("1-3 a where has 1+ digt: abc1"));
+        assert_eq!(13, column("1-3 a where : abc1"));
+        assert_eq!(30, column("1-3 a where has 1+ digit abc1"));
+        assert_eq!(27, column("1-3 a where has 1+ digit: "));
+        assert_eq!(5, column("1-3 ab where has 1+ digit: abc1"));
+        assert_eq!(6, column("1-3 a where: abc1"));
+    }
+
+    proptest! {
+        #[test]
+        fn prop_agrees_with_regex(line in r"[0-9]{1,3}-[0-9]{1,3}[ \t]{1,2}[a:é😀 ]{1,3}[ \t]{0,2}[a:é😀 ]{0,6}") {
+            if let Ok((e, _)) = entry(&line) {
+                prop_assert_eq!(Ok(e), PasswordEntry::parse_regex(&line).map_err(|_| ()));
+            }
+        }
+    }
+}
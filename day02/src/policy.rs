@@ -0,0 +1,219 @@
+use crate::fixture::Hints;
+use crate::rule::{quote, Range};
+use crate::unit::show;
+use crate::PasswordEntry;
+use std::fmt;
+
+/// What a policy makes of a password.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Verdict {
+    Valid,
+    Invalid(Violation),
+}
+
+impl Verdict {
+    pub fn is_valid(&self) -> bool {
+        *self == Verdict::Valid
+    }
+
+    pub fn violation(&self) -> Option<&Violation> {
+        match self {
+            Verdict::Valid => None,
+            Verdict::Invalid(violation) => Some(violation),
+        }
+    }
+}
+
+/// Exactly how a password breaks a policy.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Violation {
+    /// The target appears too few or too many times.
+    Count {
+        target: String,
+        count: usize,
+        left: usize,
+        right: usize,
+    },
+    /// Both positions hold the target.
+    BothPositions {
+        target: String,
+        left: usize,
+        right: usize,
+    },
+    /// Neither position holds the target. Each comes with what's there instead (with any byte
+    /// that's only part of a char escaped), or `None` when it's past the end of the password.
+    NeitherPosition {
+        target: String,
+        left: (usize, Option<String>),
+        right: (usize, Option<String>),
+    },
+    /// Too few or too many of the password's units are in a set.
+    Has {
+        set: String,
+        count: usize,
+        range: Range,
+    },
+    /// The unit at a position isn't in a set, or (`None`) the password isn't that long.
+    At {
+        pos: usize,
+        set: String,
+        found: Option<String>,
+    },
+    /// The password is too short or too long.
+    Length { length: usize, range: Range },
+    /// The password contains text it mustn't.
+    Forbidden(String),
+    /// Every part of an `and` that failed, when more than one did.
+    All(Vec<Violation>),
+    /// How each alternative of an `or` failed.
+    Any(Vec<Violation>),
+    /// The rule under a `not`, which passed.
+    Not(String),
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let found = |c: &Option<String>| match c {
+            Some(c) => format!("'{}'", c),
+            None => String::from("past the end"),
+        };
+
+        match self {
+            Violation::Count {
+                target,
+                count,
+                left,
+                right,
+            } => write!(
+                f,
+                "'{}' appears {} time{}, allowed {}-{}",
+                target,
+                count,
+                if *count == 1 { "" } else { "s" },
+                left,
+                right
+            ),
+            Violation::BothPositions {
+                target,
+                left,
+                right,
+            } => write!(
+                f,
+                "positions {} and {} both contain '{}'",
+                left, right, target
+            ),
+            Violation::NeitherPosition {
+                target,
+                left,
+                right,
+            } => write!(
+                f,
+                "neither position {} ({}) nor {} ({}) contains '{}'",
+                left.0,
+                found(&left.1),
+                right.0,
+                found(&right.1),
+                target
+            ),
+            Violation::Has { set, count, range } => {
+                write!(f, "{} of {}, needs {}", count, set, range)
+            }
+            Violation::At {
+                pos,
+                set,
+                found: Some(found),
+            } => write!(f, "position {} ('{}') isn't in {}", pos, found, set),
+            Violation::At {
+                pos,
+                set,
+                found: None,
+            } => write!(f, "position {} is past the end, needs {}", pos, set),
+            Violation::Length { length, range } => {
+                write!(f, "length {}, needs {}", length, range)
+            }
+            Violation::Forbidden(text) => write!(f, "contains forbidden {}", quote(text)),
+            Violation::All(violations) => {
+                let all: Vec<String> = violations.iter().map(|v| v.to_string()).collect();
+                f.write_str(&all.join("; "))
+            }
+            Violation::Any(violations) => {
+                let any: Vec<String> = violations.iter().map(|v| v.to_string()).collect();
+                write!(f, "none of [{}]", any.join(" | "))
+            }
+            Violation::Not(rule) => write!(f, "passes `{}`, which it mustn't", rule),
+        }
+    }
+}
+
+/// A rule every password has to follow, given the numbers and letter at the start of its line.
+///
+/// Adding one to `POLICIES` is all it takes for the binary to offer it.
+pub trait PasswordPolicy: Sync {
+    /// What it's chosen by on the command line.
+    fn name(&self) -> &'static str;
+
+    fn check(&self, entry: &PasswordEntry) -> Verdict;
+
+    /// Tells the fixture generator what's worth putting in passwords to pass or fail this
+    /// policy, beyond the target and lowercase letters it tries anyway.
+    fn hint(&self, _hints: &mut Hints) {}
+}
+
+/// The sled rental place's policy: the target appears between `left` and `right` times.
+pub struct ByCount;
+
+impl PasswordPolicy for ByCount {
+    fn name(&self) -> &'static str {
+        "count"
+    }
+
+    fn check(&self, entry: &PasswordEntry) -> Verdict {
+        let count = entry.target_count();
+        if count < entry.left || count > entry.right {
+            return Verdict::Invalid(Violation::Count {
+                target: entry.target.to_string(),
+                count,
+                left: entry.left,
+                right: entry.right,
+            });
+        }
+        Verdict::Valid
+    }
+}
+
+/// The Official Toboggan Corporate Policy: exactly one of the `left` and `right` positions
+/// (counting from 1) holds the target. Positions past the end of the password simply don't.
+pub struct ByIndex;
+
+impl PasswordPolicy for ByIndex {
+    fn name(&self) -> &'static str {
+        "index"
+    }
+
+    fn check(&self, entry: &PasswordEntry) -> Verdict {
+        let l = entry.left_unit();
+        let r = entry.right_unit();
+        let target = Some(entry.target.as_bytes());
+        match (l == target, r == target) {
+            (true, false) | (false, true) => Verdict::Valid,
+            (true, true) => Verdict::Invalid(Violation::BothPositions {
+                target: entry.target.to_string(),
+                left: entry.left,
+                right: entry.right,
+            }),
+            (false, false) => Verdict::Invalid(Violation::NeitherPosition {
+                target: entry.target.to_string(),
+                left: (entry.left, l.map(show)),
+                right: (entry.right, r.map(show)),
+            }),
+        }
+    }
+}
+
+/// Every policy there is, the puzzle's two parts first.
+pub const POLICIES: &[&dyn PasswordPolicy] = &[&ByCount, &ByIndex];
+
+/// Looks a policy up by name.
+pub fn find(name: &str) -> Option<&'static dyn PasswordPolicy> {
+    POLICIES.iter().copied().find(|p| p.name() == name)
+}
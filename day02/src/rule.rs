@@ -0,0 +1,760 @@
+use crate::fixture::Hints;
+use crate::policy::{self, PasswordPolicy, Verdict, Violation};
+use crate::PasswordEntry;
+use aoc::ParseError;
+use std::fmt;
+use std::str::FromStr;
+
+/// A policy built out of smaller rules, e.g.
+///
+/// ```text
+/// min-length 12 and has 1+ digit and has 1+ upper and forbid "password"
+/// ```
+///
+/// `not` binds tightest, then `and`, then `or`; parentheses group. The atoms are:
+///
+/// - `has <range> <set>`: how many of the password's units are in the set
+/// - `at <pos> <set>`: the unit at a position (counting from 1) is in the set
+/// - `length <range>`, `min-length <n>`, `max-length <n>`
+/// - `forbid "<text>"`: the password doesn't contain the text anywhere
+/// - the name of any policy in `POLICIES`, which checks the line's own numbers and target
+///
+/// A range is `n`, `n+` or `n-m`. A set is a quoted string of units (any of which count) or a
+/// class: `lower`, `upper`, `digit`, `alpha`, `symbol` or `space`. Units are whatever the entry
+/// measures its positions in.
+pub enum Rule {
+    And(Vec<Rule>),
+    Or(Vec<Rule>),
+    Not(Box<Rule>),
+    Policy(&'static dyn PasswordPolicy),
+    Has { range: Range, set: Set },
+    At { pos: usize, set: Set },
+    Length(Range),
+    Forbid(String),
+}
+
+/// How many of something are allowed, with no upper limit when `max` is `None`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Range {
+    pub min: usize,
+    pub max: Option<usize>,
+}
+
+impl Range {
+    pub fn contains(&self, n: usize) -> bool {
+        n >= self.min && self.max.is_none_or(|max| n <= max)
+    }
+}
+
+impl fmt::Display for Range {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.max {
+            Some(max) if max == self.min => write!(f, "{}", max),
+            Some(max) => write!(f, "{}-{}", self.min, max),
+            None => write!(f, "{}+", self.min),
+        }
+    }
+}
+
+impl FromStr for Range {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let number = |n: &str| {
+            n.parse::<usize>()
+                .map_err(|_| format!("expected a range like 3, 3+ or 1-3, not '{}'", s))
+        };
+
+        let range = if let Some(min) = s.strip_suffix('+') {
+            Range {
+                min: number(min)?,
+                max: None,
+            }
+        } else if let Some((min, max)) = s.split_once('-') {
+            Range {
+                min: number(min)?,
+                max: Some(number(max)?),
+            }
+        } else {
+            let n = number(s)?;
+            Range {
+                min: n,
+                max: Some(n),
+            }
+        };
+
+        if range.max.is_some_and(|max| max < range.min) {
+            return Err(format!("range '{}' is empty", s));
+        }
+        Ok(range)
+    }
+}
+
+/// The units a `has` or `at` rule is looking for.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Set {
+    /// Any unit of the string.
+    Units(String),
+    Class(Class),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Class {
+    Lower,
+    Upper,
+    Digit,
+    Alpha,
+    /// Anything that isn't a letter, digit or whitespace.
+    Symbol,
+    Space,
+}
+
+impl Class {
+    const ALL: [Class; 6] = [
+        Class::Lower,
+        Class::Upper,
+        Class::Digit,
+        Class::Alpha,
+        Class::Symbol,
+        Class::Space,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Class::Lower => "lower",
+            Class::Upper => "upper",
+            Class::Digit => "digit",
+            Class::Alpha => "alpha",
+            Class::Symbol => "symbol",
+            Class::Space => "space",
+        }
+    }
+
+    /// A few units of the class for the fixture generator to try. There's no point offering
+    /// whitespace, since a password on a line can't have any.
+    fn examples(self) -> &'static str {
+        match self {
+            Class::Lower => "abcxyz",
+            Class::Upper => "ABCXYZ",
+            Class::Digit => "0123456789",
+            Class::Alpha => "aZéß",
+            Class::Symbol => "!@#$%&*?",
+            Class::Space => "",
+        }
+    }
+
+    fn matches(self, c: char) -> bool {
+        match self {
+            Class::Lower => c.is_lowercase(),
+            Class::Upper => c.is_uppercase(),
+            Class::Digit => c.is_numeric(),
+            Class::Alpha => c.is_alphabetic(),
+            Class::Symbol => !c.is_alphanumeric() && !c.is_whitespace(),
+            Class::Space => c.is_whitespace(),
+        }
+    }
+}
+
+impl Set {
+    /// Whether a unit of `entry`'s password is in the set. A class goes by the unit's first
+    /// char, and a byte that's only part of a char is in none of them.
+    fn contains(&self, entry: &PasswordEntry, unit: &[u8]) -> bool {
+        match self {
+            Set::Units(units) => entry.unit.split(units).any(|u| u == unit),
+            Set::Class(class) => std::str::from_utf8(unit)
+                .ok()
+                .and_then(|s| s.chars().next())
+                .is_some_and(|c| class.matches(c)),
+        }
+    }
+}
+
+impl fmt::Display for Set {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Set::Units(units) => f.write_str(&quote(units)),
+            Set::Class(class) => f.write_str(class.name()),
+        }
+    }
+}
+
+impl PasswordPolicy for Rule {
+    fn name(&self) -> &'static str {
+        "rule"
+    }
+
+    fn hint(&self, hints: &mut Hints) {
+        let mut set = |set: &Set| match set {
+            Set::Units(units) => hints.units(units),
+            Set::Class(class) => hints.units(class.examples()),
+        };
+
+        match self {
+            Rule::And(rules) | Rule::Or(rules) => rules.iter().for_each(|r| r.hint(hints)),
+            Rule::Not(rule) => rule.hint(hints),
+            Rule::Policy(policy) => policy.hint(hints),
+            Rule::Has { range, set: s } => {
+                set(s);
+                hints.length(range.max.unwrap_or(range.min) + 2);
+            }
+            Rule::At { pos, set: s } => {
+                set(s);
+                hints.length(pos + 2);
+            }
+            Rule::Length(range) => hints.length(range.max.unwrap_or(range.min) + 2),
+            Rule::Forbid(text) => hints.text(text),
+        }
+    }
+
+    fn check(&self, entry: &PasswordEntry) -> Verdict {
+        let invalid = Verdict::Invalid;
+        let password = entry.password;
+
+        match self {
+            Rule::And(rules) => {
+                let mut failed: Vec<Violation> = rules
+                    .iter()
+                    .filter_map(|r| r.check(entry).violation().cloned())
+                    .collect();
+                match failed.len() {
+                    0 => Verdict::Valid,
+                    1 => invalid(failed.remove(0)),
+                    _ => invalid(Violation::All(failed)),
+                }
+            }
+            Rule::Or(rules) => {
+                let mut failed = Vec::new();
+                for rule in rules {
+                    match rule.check(entry) {
+                        Verdict::Valid => return Verdict::Valid,
+                        Verdict::Invalid(violation) => failed.push(violation),
+                    }
+                }
+                invalid(Violation::Any(failed))
+            }
+            Rule::Not(rule) => match rule.check(entry) {
+                Verdict::Valid => invalid(Violation::Not(rule.to_string())),
+                Verdict::Invalid(_) => Verdict::Valid,
+            },
+            Rule::Policy(policy) => policy.check(entry),
+            Rule::Has { range, set } => {
+                let count = entry
+                    .unit
+                    .split(password)
+                    .filter(|u| set.contains(entry, u))
+                    .count();
+                if range.contains(count) {
+                    return Verdict::Valid;
+                }
+                invalid(Violation::Has {
+                    set: set.to_string(),
+                    count,
+                    range: *range,
+                })
+            }
+            Rule::At { pos, set } => {
+                let found = entry.unit.at(password, *pos);
+                if found.is_some_and(|u| set.contains(entry, u)) {
+                    return Verdict::Valid;
+                }
+                invalid(Violation::At {
+                    pos: *pos,
+                    set: set.to_string(),
+                    found: found.map(crate::unit::show),
+                })
+            }
+            Rule::Length(range) => {
+                let length = entry.unit.split(password).count();
+                if range.contains(length) {
+                    return Verdict::Valid;
+                }
+                invalid(Violation::Length {
+                    length,
+                    range: *range,
+                })
+            }
+            Rule::Forbid(text) => {
+                if !password.contains(text.as_str()) {
+                    return Verdict::Valid;
+                }
+                invalid(Violation::Forbidden(text.clone()))
+            }
+        }
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // what binds looser than the rule around it needs parentheses, and so does the same
+        // combinator nested in itself so it reads back the same way
+        let grouped = |rule: &Rule, loose: fn(&Rule) -> bool| {
+            if loose(rule) {
+                format!("({})", rule)
+            } else {
+                rule.to_string()
+            }
+        };
+        let join = |rules: &[Rule], op: &str, loose: fn(&Rule) -> bool| {
+            rules
+                .iter()
+                .map(|r| grouped(r, loose))
+                .collect::<Vec<_>>()
+                .join(op)
+        };
+
+        match self {
+            Rule::And(rules) => f.write_str(&join(rules, " and ", |r| {
+                matches!(r, Rule::And(_) | Rule::Or(_))
+            })),
+            Rule::Or(rules) => f.write_str(&join(rules, " or ", |r| matches!(r, Rule::Or(_)))),
+            Rule::Not(rule) => write!(
+                f,
+                "not {}",
+                grouped(rule, |r| matches!(r, Rule::And(_) | Rule::Or(_)))
+            ),
+            Rule::Policy(policy) => f.write_str(policy.name()),
+            Rule::Has { range, set } => write!(f, "has {} {}", range, set),
+            Rule::At { pos, set } => write!(f, "at {} {}", pos, set),
+            Rule::Length(range) => write!(f, "length {}", range),
+            Rule::Forbid(text) => write!(f, "forbid {}", quote(text)),
+        }
+    }
+}
+
+/// Quotes text the way a rule reads it back: only `"` and `\` are escaped.
+pub(crate) fn quote(text: &str) -> String {
+    let escaped = text.replace('\\', "\\\\").replace('"', "\\\"");
+    format!("\"{}\"", escaped)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token<'a> {
+    Open,
+    Close,
+    Word(&'a str),
+    Quoted(String),
+}
+
+/// Splits a rule into tokens, each with the byte offset it starts at.
+fn tokens(text: &str) -> Result<Vec<(usize, Token<'_>)>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut chars = text.char_indices().peekable();
+
+    while let Some(&(start, c)) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' | ')' => {
+                chars.next();
+                let token = if c == '(' { Token::Open } else { Token::Close };
+                tokens.push((start, token));
+            }
+            '"' => {
+                chars.next();
+                let mut quoted = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, '"')) => break,
+                        Some((i, '\\')) => match chars.next() {
+                            Some((_, c @ '"')) | Some((_, c @ '\\')) => quoted.push(c),
+                            _ => {
+                                return Err(ParseError::new(
+                                    text,
+                                    i + 1,
+                                    "only \\\" and \\\\ can be escaped",
+                                ))
+                            }
+                        },
+                        Some((_, c)) => quoted.push(c),
+                        None => {
+                            return Err(ParseError::new(text, start + 1, "unterminated string"))
+                        }
+                    }
+                }
+                tokens.push((start, Token::Quoted(quoted)));
+            }
+            _ => {
+                let end = text[start..]
+                    .find(|c: char| c.is_whitespace() || "()\"".contains(c))
+                    .map_or(text.len(), |len| start + len);
+                tokens.push((start, Token::Word(&text[start..end])));
+                while chars.peek().is_some_and(|&(i, _)| i < end) {
+                    chars.next();
+                }
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// How deep parentheses and `not`s can nest, so a rule can't parse its way off the end of the
+/// stack.
+const MAX_DEPTH: usize = 256;
+
+struct Parser<'a> {
+    text: &'a str,
+    tokens: Vec<(usize, Token<'a>)>,
+    next: usize,
+    /// How many parentheses and `not`s the parser is inside.
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, offset: usize, message: impl Into<String>) -> ParseError {
+        ParseError::new(self.text, offset + 1, message)
+    }
+
+    /// Where the next token starts, or the end of the rule when there isn't one.
+    fn offset(&self) -> usize {
+        self.tokens
+            .get(self.next)
+            .map_or(self.text.len(), |&(offset, _)| offset)
+    }
+
+    fn token(&mut self) -> Option<(usize, Token<'a>)> {
+        let token = self.tokens.get(self.next).cloned();
+        self.next += 1;
+        token
+    }
+
+    /// Goes one level deeper into the rule, failing at `offset` past `MAX_DEPTH`. Each call is
+    /// undone by `self.depth -= 1` once the nested part is parsed.
+    fn nest(&mut self, offset: usize) -> Result<(), ParseError> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(self.error(offset, "rule is nested too deeply"));
+        }
+        Ok(())
+    }
+
+    /// Moves past the next token if it's the keyword.
+    fn keyword(&mut self, keyword: &str) -> bool {
+        match self.tokens.get(self.next) {
+            Some((_, Token::Word(w))) if w.eq_ignore_ascii_case(keyword) => {
+                self.next += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn or(&mut self) -> Result<Rule, ParseError> {
+        let mut rules = vec![self.and()?];
+        while self.keyword("or") {
+            rules.push(self.and()?);
+        }
+        Ok(if rules.len() == 1 {
+            rules.remove(0)
+        } else {
+            Rule::Or(rules)
+        })
+    }
+
+    fn and(&mut self) -> Result<Rule, ParseError> {
+        let mut rules = vec![self.not()?];
+        while self.keyword("and") {
+            rules.push(self.not()?);
+        }
+        Ok(if rules.len() == 1 {
+            rules.remove(0)
+        } else {
+            Rule::And(rules)
+        })
+    }
+
+    fn not(&mut self) -> Result<Rule, ParseError> {
+        let offset = self.offset();
+        if self.keyword("not") {
+            self.nest(offset)?;
+            let rule = self.not()?;
+            self.depth -= 1;
+            return Ok(Rule::Not(Box::new(rule)));
+        }
+        self.atom()
+    }
+
+    fn atom(&mut self) -> Result<Rule, ParseError> {
+        let offset = self.offset();
+        let word = match self.token() {
+            Some((_, Token::Open)) => {
+                self.nest(offset)?;
+                let rule = self.or()?;
+                self.depth -= 1;
+                return match self.token() {
+                    Some((_, Token::Close)) => Ok(rule),
+                    _ => Err(self.error(offset, "unclosed '('")),
+                };
+            }
+            Some((_, Token::Word(word))) => word.to_ascii_lowercase(),
+            _ => return Err(self.error(offset, "expected a rule")),
+        };
+
+        Ok(match word.as_str() {
+            "has" => Rule::Has {
+                range: self.parse("range")?,
+                set: self.set()?,
+            },
+            "at" => {
+                let pos_offset = self.offset();
+                let pos = self.parse::<usize>("position")?;
+                if pos == 0 {
+                    return Err(self.error(pos_offset, "position must be at least 1"));
+                }
+                Rule::At {
+                    pos,
+                    set: self.set()?,
+                }
+            }
+            "length" => Rule::Length(self.parse("range")?),
+            "min-length" => Rule::Length(Range {
+                min: self.parse("length")?,
+                max: None,
+            }),
+            "max-length" => Rule::Length(Range {
+                min: 0,
+                max: Some(self.parse("length")?),
+            }),
+            "forbid" => match self.token() {
+                Some((_, Token::Quoted(text))) if !text.is_empty() => Rule::Forbid(text),
+                _ => return Err(self.error(offset, "expected text to forbid, like \"1234\"")),
+            },
+            _ => match policy::find(&word) {
+                Some(policy) => Rule::Policy(policy),
+                None => return Err(self.error(offset, format!("unknown rule '{}'", word))),
+            },
+        })
+    }
+
+    /// Parses the next token, which has to be a word.
+    fn parse<T: FromStr>(&mut self, what: &str) -> Result<T, ParseError>
+    where
+        T::Err: fmt::Display,
+    {
+        let offset = self.offset();
+        match self.token() {
+            Some((_, Token::Word(word))) => word
+                .parse()
+                .map_err(|e| self.error(offset, format!("invalid {}: {}", what, e))),
+            _ => Err(self.error(offset, format!("expected a {}", what))),
+        }
+    }
+
+    fn set(&mut self) -> Result<Set, ParseError> {
+        let offset = self.offset();
+        let class = match self.token() {
+            Some((_, Token::Quoted(units))) if !units.is_empty() => return Ok(Set::Units(units)),
+            Some((_, Token::Word(word))) => Class::ALL
+                .iter()
+                .copied()
+                .find(|c| word.eq_ignore_ascii_case(c.name())),
+            _ => None,
+        };
+        class.map(Set::Class).ok_or_else(|| {
+            self.error(
+                offset,
+                "expected a quoted string or one of lower, upper, digit, alpha, symbol, space",
+            )
+        })
+    }
+}
+
+impl FromStr for Rule {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            text: s,
+            tokens: tokens(s)?,
+            next: 0,
+            depth: 0,
+        };
+        let rule = parser.or()?;
+        if parser.next < parser.tokens.len() {
+            return Err(parser.error(parser.offset(), "expected 'and', 'or' or the end"));
+        }
+        Ok(rule)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn entry(line: &str) -> PasswordEntry<'_> {
+        PasswordEntry::parse(line).unwrap()
+    }
+
+    fn explain(rule: &str, line: &str) -> Option<String> {
+        let rule: Rule = rule.parse().unwrap();
+        rule.check(&entry(line)).violation().map(|v| v.to_string())
+    }
+
+    #[test]
+    fn test_corporate() {
+        let rule = "min-length 12 and has 1+ digit and has 1+ upper and forbid \"password\"";
+        assert_eq!(None, explain(rule, "1-1 a: Tr0ub4dor&3xyz"));
+        assert_eq!(
+            Some(String::from("length 8, needs 12+; 0 of digit, needs 1+")),
+            explain(rule, "1-1 a: Abcdefgh")
+        );
+        assert_eq!(
+            Some(String::from("contains forbidden \"password\"")),
+            explain(rule, "1-1 a: Mypassword123")
+        );
+    }
+
+    #[test]
+    fn test_combinators() {
+        assert_eq!(None, explain("has 2 \"ab\" or at 1 digit", "1-1 x: 1xyz"));
+        assert_eq!(
+            Some(String::from(
+                "none of [4 of \"ab\", needs 2 | position 1 ('a') isn't in digit]"
+            )),
+            explain("has 2 \"ab\" or at 1 digit", "1-1 x: abab")
+        );
+        assert_eq!(
+            Some(String::from("passes `has 1+ symbol`, which it mustn't")),
+            explain("NOT has 1+ symbol", "1-1 x: ab!")
+        );
+        assert_eq!(
+            Some(String::from("position 9 is past the end, needs lower")),
+            explain("at 9 lower", "1-1 x: ab")
+        );
+    }
+
+    #[test]
+    fn test_line_policies() {
+        // the puzzle's own policies, alongside something the line's prefix can't say
+        let rule = "count and min-length 5";
+        assert_eq!(None, explain(rule, "1-3 a: abcde"));
+        assert_eq!(
+            Some(String::from("'b' appears 0 times, allowed 1-3")),
+            explain(rule, "1-3 b: cdefg")
+        );
+        assert_eq!(
+            Some(String::from(
+                "none of [positions 2 and 9 both contain 'c' | length 9, needs 0-3]"
+            )),
+            explain("index or max-length 3", "2-9 c: ccccccccc")
+        );
+    }
+
+    #[test]
+    fn test_unicode() {
+        let mut e = entry("1-1 x: Ünïcödé👍🏽");
+        let rule: Rule = "has 4 \"üïöéÜ\" and has 1 symbol and length 8"
+            .parse()
+            .unwrap();
+        // the skin tone is a symbol of its own in chars
+        assert!(!rule.check(&e).is_valid());
+        e.unit = crate::Unit::Graphemes;
+        assert!(rule.check(&e).is_valid());
+        e.unit = crate::Unit::Bytes;
+        assert!(!rule.check(&e).is_valid());
+    }
+
+    #[test]
+    fn test_display() {
+        for (rule, shown) in [
+            ("has 1+ digit", "has 1+ digit"),
+            ("a or b", ""),
+            (
+                "(count OR index) and not (min-length 3 or forbid \"a\\\"b\")",
+                "(count or index) and not (length 3+ or forbid \"a\\\"b\")",
+            ),
+            ("not not at 2 \"x\"", "not not at 2 \"x\""),
+            ("forbid \"a\nb\\\\\"", "forbid \"a\nb\\\\\""),
+        ] {
+            match rule.parse::<Rule>() {
+                Ok(parsed) => {
+                    let shown = if shown.is_empty() { rule } else { shown };
+                    assert_eq!(shown, parsed.to_string());
+                    assert_eq!(shown, shown.parse::<Rule>().unwrap().to_string());
+                }
+                Err(e) => assert!(shown.is_empty(), "{}", e),
+            }
+        }
+        assert_eq!(
+            "length 1-4 or length 0-9 and has 0 space",
+            "length 1-4 or max-length 9 and has 0 space"
+                .parse::<Rule>()
+                .unwrap()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn test_malformed() {
+        let column = |rule: &str| rule.parse::<Rule>().map(|_| ()).unwrap_err().column;
+        assert_eq!(1, column("a or b"));
+        assert_eq!(5, column("has lots digit"));
+        assert_eq!(8, column("has 1+ vowel"));
+        assert_eq!(4, column("at 0 digit"));
+        assert_eq!(1, column("(count and index"));
+        assert_eq!(13, column("has 1 digit has 2 upper"));
+        assert_eq!(8, column("forbid \"abc"));
+        assert_eq!(5, column("has 3-1 \"a\""));
+        assert_eq!(9, column("count or"));
+    }
+
+    #[test]
+    fn test_nesting() {
+        let nested =
+            |depth: usize| format!("{}has 1+ digit{}", "(not ".repeat(depth), ")".repeat(depth));
+        let error = |rule: &str| rule.parse::<Rule>().map(|_| ()).unwrap_err();
+
+        // each level is a '(' and a `not`
+        assert!(nested(MAX_DEPTH / 2).parse::<Rule>().is_ok());
+        let e = error(&nested(MAX_DEPTH / 2 + 1));
+        assert_eq!("rule is nested too deeply", e.message);
+        assert_eq!(5 * (MAX_DEPTH / 2) + 1, e.column);
+
+        // deep enough to overflow the stack without the limit
+        let deep = format!("{}count{}", "(".repeat(20_000), ")".repeat(20_000));
+        assert_eq!("rule is nested too deeply", error(&deep).message);
+        let deep = format!("{}count", "not ".repeat(20_000));
+        assert_eq!("rule is nested too deeply", error(&deep).message);
+    }
+
+    fn rule() -> impl Strategy<Value = String> {
+        let atom = prop_oneof![
+            (0usize..4, "[ab1!]{1,2}").prop_map(|(n, s)| format!("has {}+ \"{}\"", n, s)),
+            (1usize..6, "(lower|upper|digit|symbol)").prop_map(|(p, c)| format!("at {} {}", p, c)),
+            (0usize..6).prop_map(|n| format!("min-length {}", n)),
+            "[ab1]{1,2}".prop_map(|s| format!("forbid \"{}\"", s)),
+        ];
+        atom.prop_recursive(3, 12, 2, |inner| {
+            prop_oneof![
+                (inner.clone(), inner.clone()).prop_map(|(a, b)| format!("({}) and ({})", a, b)),
+                (inner.clone(), inner.clone()).prop_map(|(a, b)| format!("({}) or ({})", a, b)),
+                inner.prop_map(|a| format!("not ({})", a)),
+            ]
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn prop_de_morgan(a in rule(), b in rule(), password in "[abAB1!]{1,6}") {
+            let line = format!("1-1 a: {}", password);
+            let e = entry(&line);
+            let check = |rule: String| rule.parse::<Rule>().unwrap().check(&e).is_valid();
+
+            let both = check(format!("({}) and ({})", a, b));
+            prop_assert_eq!(both, check(a.clone()) && check(b.clone()));
+            prop_assert_eq!(!both, check(format!("not ({}) or not ({})", a, b)));
+        }
+
+        #[test]
+        fn prop_display_round_trip(rule in rule(), password in "[abAB1!]{1,6}") {
+            let line = format!("1-1 a: {}", password);
+            let e = entry(&line);
+            let parsed: Rule = rule.parse().unwrap();
+            let shown: Rule = parsed.to_string().parse().unwrap();
+            prop_assert_eq!(parsed.to_string(), shown.to_string());
+            prop_assert_eq!(parsed.check(&e), shown.check(&e));
+        }
+    }
+}
@@ -0,0 +1,249 @@
+use crate::{PasswordDatabase, PasswordPolicy};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::{self, Write};
+
+/// How wide the longest bar in a histogram is drawn.
+const BAR: usize = 40;
+
+/// Aggregate figures for a database, checked against some policies.
+#[derive(Debug, PartialEq)]
+pub struct Stats {
+    pub entries: usize,
+    /// How many entries pass each policy, in the order the policies were given.
+    pub passes: Vec<(&'static str, usize)>,
+    /// How many entries have each target.
+    pub targets: BTreeMap<String, usize>,
+    /// How many passwords are each length, in the database's unit.
+    pub lengths: BTreeMap<usize, usize>,
+    /// How many entries have each left position.
+    pub lefts: BTreeMap<usize, usize>,
+    /// How many entries have each right position.
+    pub rights: BTreeMap<usize, usize>,
+}
+
+impl Stats {
+    pub fn new(db: &PasswordDatabase, policies: &[&dyn PasswordPolicy]) -> Self {
+        let mut passes: Vec<(&'static str, usize)> =
+            policies.iter().map(|p| (p.name(), 0)).collect();
+        let mut targets = BTreeMap::new();
+        let mut lengths = BTreeMap::new();
+        let mut lefts = BTreeMap::new();
+        let mut rights = BTreeMap::new();
+
+        for (_, entry, verdicts) in db.verdicts(policies) {
+            for (verdict, (_, passed)) in verdicts.iter().zip(passes.iter_mut()) {
+                if verdict.is_valid() {
+                    *passed += 1;
+                }
+            }
+            *targets.entry(String::from(entry.target)).or_insert(0) += 1;
+            *lengths
+                .entry(entry.unit.length(entry.password))
+                .or_insert(0) += 1;
+            *lefts.entry(entry.left).or_insert(0) += 1;
+            *rights.entry(entry.right).or_insert(0) += 1;
+        }
+
+        Stats {
+            entries: db.entries().len(),
+            passes,
+            targets,
+            lengths,
+            lefts,
+            rights,
+        }
+    }
+}
+
+fn percent(count: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        100.0 * count as f64 / total as f64
+    }
+}
+
+/// Writes one line per key: its count, its share of `total` and a bar scaled to the largest.
+fn histogram<K: fmt::Display>(
+    f: &mut fmt::Formatter<'_>,
+    title: &str,
+    counts: &BTreeMap<K, usize>,
+    total: usize,
+) -> fmt::Result {
+    writeln!(f, "\n{}:", title)?;
+    let most = counts.values().copied().max().unwrap_or(0);
+    for (key, &count) in counts {
+        let bar = (count * BAR).div_ceil(most);
+        writeln!(
+            f,
+            "  {:>6} {:>8} {:>6.1}%  {}",
+            key.to_string(),
+            count,
+            percent(count, total),
+            "#".repeat(bar)
+        )?;
+    }
+    Ok(())
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "entries: {}", self.entries)?;
+        writeln!(f, "\npass rates:")?;
+        for (name, passed) in &self.passes {
+            writeln!(
+                f,
+                "  {:<6} {:>8} {:>6.1}%",
+                name,
+                passed,
+                percent(*passed, self.entries)
+            )?;
+        }
+        histogram(f, "targets", &self.targets, self.entries)?;
+        histogram(f, "password lengths", &self.lengths, self.entries)?;
+        histogram(f, "left positions", &self.lefts, self.entries)?;
+        histogram(f, "right positions", &self.rights, self.entries)
+    }
+}
+
+/// Quotes a CSV field if it needs it, doubling any quotes inside.
+fn field(s: &str) -> Cow<'_, str> {
+    if s.contains([',', '"', '\r', '\n']) {
+        Cow::Owned(format!("\"{}\"", s.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(s)
+    }
+}
+
+/// Writes the database as CSV: a header, then a row per entry with its line number, its fields
+/// (the rule is empty for a line without one) and whether it's `valid` or `invalid` under each
+/// policy.
+pub fn write_csv(
+    out: &mut impl Write,
+    db: &PasswordDatabase,
+    policies: &[&dyn PasswordPolicy],
+) -> io::Result<()> {
+    write!(out, "line,left,right,target,rule,password")?;
+    for policy in policies {
+        write!(out, ",{}", field(policy.name()))?;
+    }
+    writeln!(out)?;
+
+    for (line, entry, verdicts) in db.verdicts(policies) {
+        write!(
+            out,
+            "{},{},{},{},{},{}",
+            line,
+            entry.left,
+            entry.right,
+            field(entry.target),
+            field(entry.rule().unwrap_or("")),
+            field(entry.password)
+        )?;
+        for verdict in verdicts {
+            let valid = verdict.is_valid();
+            write!(out, ",{}", if valid { "valid" } else { "invalid" })?;
+        }
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{ByCount, ByIndex, POLICIES};
+    use aoc::Solution;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_sample() {
+        let db = PasswordDatabase::parse("1-3 a: abcde\n1-3 b: cdefg\n2-9 c: ccccccccc").unwrap();
+        let stats = Stats::new(&db, POLICIES);
+        assert_eq!(3, stats.entries);
+        assert_eq!(vec![("count", 2), ("index", 1)], stats.passes);
+        let targets: Vec<(&str, usize)> = stats
+            .targets
+            .iter()
+            .map(|(t, &n)| (t.as_str(), n))
+            .collect();
+        assert_eq!(vec![("a", 1), ("b", 1), ("c", 1)], targets);
+        assert_eq!(
+            vec![(5, 2), (9, 1)],
+            stats.lengths.into_iter().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![(1, 2), (2, 1)],
+            stats.lefts.into_iter().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![(3, 2), (9, 1)],
+            stats.rights.into_iter().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        let db = PasswordDatabase::parse("1-3 a: abcde\n1-3 b: cdefg").unwrap();
+        let report = Stats::new(&db, &[&ByCount]).to_string();
+        assert!(report.starts_with("entries: 2\n\npass rates:\n  count         1   50.0%\n"));
+        assert!(report.contains(
+            "\ntargets:\n       a        1   50.0%  ########################################\n"
+        ));
+
+        let empty = PasswordDatabase::parse("").unwrap();
+        assert!(Stats::new(&empty, &[&ByCount])
+            .to_string()
+            .contains("count         0    0.0%"));
+    }
+
+    #[test]
+    fn test_csv() {
+        let db = PasswordDatabase::parse(
+            "1-3 a: abcde\n1-3 \": a,\"b\n2-9 c: ccccccccc\n1-3 a where forbid \",\": a,b",
+        )
+        .unwrap();
+        let mut out = Vec::new();
+        write_csv(&mut out, &db, &[&ByCount, &ByIndex]).unwrap();
+        assert_eq!(
+            "line,left,right,target,rule,password,count,index\n\
+             1,1,3,a,,abcde,valid,valid\n\
+             2,1,3,\"\"\"\",,\"a,\"\"b\",valid,valid\n\
+             3,2,9,c,,ccccccccc,valid,invalid\n\
+             4,1,3,a,\"forbid \"\",\"\"\",\"a,b\",invalid,invalid\n",
+            String::from_utf8(out).unwrap()
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn prop_histograms_cover_every_entry(
+            lines in prop::collection::vec((1..5usize, 0..5usize, "[a-c]", "[a-c,\"]{1,8}"), 0..20)
+        ) {
+            let input: Vec<String> = lines
+                .iter()
+                .map(|(left, extra, target, password)| {
+                    format!("{}-{} {}: {}", left, left + extra, target, password)
+                })
+                .collect();
+            let db = PasswordDatabase::parse(&input.join("\n")).unwrap();
+            let stats = Stats::new(&db, POLICIES);
+            for counts in [&stats.lengths, &stats.lefts, &stats.rights] {
+                prop_assert_eq!(lines.len(), counts.values().sum::<usize>());
+            }
+            prop_assert_eq!(lines.len(), stats.targets.values().sum::<usize>());
+
+            // every row has the same number of fields, however the password is quoted
+            let mut out = Vec::new();
+            write_csv(&mut out, &db, POLICIES).unwrap();
+            let csv = String::from_utf8(out).unwrap();
+            prop_assert_eq!(lines.len() + 1, csv.lines().count());
+            for row in csv.lines() {
+                let unquoted = row.split('"').step_by(2).collect::<String>();
+                prop_assert_eq!(7, unquoted.matches(',').count());
+            }
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use std::fmt;
+use std::str::FromStr;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// What a policy's positions and counts are measured in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Unit {
+    /// UTF-8 bytes, so anything past ASCII takes up more than one position.
+    Bytes,
+    /// Unicode scalar values, so an accent combined onto a letter is a position of its own.
+    #[default]
+    Chars,
+    /// Extended grapheme clusters: what a reader would call one character, flags and all.
+    Graphemes,
+}
+
+impl Unit {
+    pub const ALL: [Unit; 3] = [Unit::Bytes, Unit::Chars, Unit::Graphemes];
+
+    /// Splits `s` into units.
+    pub fn split(self, s: &str) -> Box<dyn Iterator<Item = &[u8]> + '_> {
+        match self {
+            Unit::Bytes => Box::new(s.as_bytes().chunks(1)),
+            Unit::Chars => Box::new(s.char_indices().map(move |(i, c)| {
+                let end = i + c.len_utf8();
+                &s.as_bytes()[i..end]
+            })),
+            Unit::Graphemes => Box::new(s.graphemes(true).map(str::as_bytes)),
+        }
+    }
+
+    /// The unit at `pos` (counting from 1), or `None` past the end of `s`.
+    pub fn at(self, s: &str, pos: usize) -> Option<&[u8]> {
+        let i = pos.checked_sub(1)?;
+        // every unit is a byte in plain ASCII, so skip the walk through the string
+        if self == Unit::Bytes || s.is_ascii() {
+            return s.as_bytes().get(i..=i);
+        }
+        self.split(s).nth(i)
+    }
+
+    /// How many units long `s` is.
+    pub fn length(self, s: &str) -> usize {
+        if self == Unit::Bytes || s.is_ascii() {
+            return s.len();
+        }
+        self.split(s).count()
+    }
+
+    /// How many units of `s` are exactly `target`.
+    pub fn count(self, s: &str, target: &str) -> usize {
+        self.split(s).filter(|&u| u == target.as_bytes()).count()
+    }
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Unit::Bytes => "bytes",
+            Unit::Chars => "chars",
+            Unit::Graphemes => "graphemes",
+        })
+    }
+}
+
+impl FromStr for Unit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Unit::ALL
+            .iter()
+            .copied()
+            .find(|u| u.to_string() == s)
+            .ok_or_else(|| String::from("expected bytes, chars or graphemes"))
+    }
+}
+
+/// Shows a unit as text, escaping a byte that's only part of a char.
+pub fn show(unit: &[u8]) -> String {
+    match std::str::from_utf8(unit) {
+        Ok(s) => String::from(s),
+        Err(_) => unit.iter().map(|b| format!("\\x{:02x}", b)).collect(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_accented() {
+        // "é" written as one char, then as "e" with a combining acute accent
+        for (s, bytes, chars) in [("café", 5, 4), ("cafe\u{301}", 6, 5)] {
+            assert_eq!(bytes, Unit::Bytes.split(s).count());
+            assert_eq!(chars, Unit::Chars.split(s).count());
+            assert_eq!(4, Unit::Graphemes.split(s).count());
+        }
+
+        assert_eq!(Some("é".as_bytes()), Unit::Chars.at("café", 4));
+        assert_eq!(Some("e".as_bytes()), Unit::Chars.at("cafe\u{301}", 4));
+        assert_eq!(
+            Some("e\u{301}".as_bytes()),
+            Unit::Graphemes.at("cafe\u{301}", 4)
+        );
+        assert_eq!(Some(&[0xc3][..]), Unit::Bytes.at("café", 4));
+        assert_eq!("\\xc3", show(Unit::Bytes.at("café", 4).unwrap()));
+    }
+
+    #[test]
+    fn test_emoji() {
+        // a thumbs up with a skin tone, then a family joined with zero-width joiners
+        let s = "👍🏽a👨‍👩‍👧";
+        assert_eq!(Some("👍🏽".as_bytes()), Unit::Graphemes.at(s, 1));
+        assert_eq!(Some("a".as_bytes()), Unit::Graphemes.at(s, 2));
+        assert_eq!(Some("👨‍👩‍👧".as_bytes()), Unit::Graphemes.at(s, 3));
+        assert_eq!(None, Unit::Graphemes.at(s, 4));
+        assert_eq!(Some("🏽".as_bytes()), Unit::Chars.at(s, 2));
+
+        assert_eq!(1, Unit::Chars.count(s, "👍"));
+        assert_eq!(0, Unit::Graphemes.count(s, "👍"));
+        assert_eq!(1, Unit::Graphemes.count(s, "👍🏽"));
+    }
+
+    #[test]
+    fn test_ascii() {
+        for unit in Unit::ALL.iter() {
+            assert_eq!(Some(&b"c"[..]), unit.at("abcde", 3));
+            assert_eq!(None, unit.at("abcde", 6));
+            assert_eq!(None, unit.at("abcde", 0));
+            assert_eq!(2, unit.count("abcbe", "b"));
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use crate::{slopes, Map};
+use aoc::rand::Rng;
+use aoc::{Generate, Generated};
+
+impl Generate for Map {
+    /// A random forest `size` rows tall. Trees are counted by walking each slope's coordinates
+    /// directly rather than scanning rows the way the solver does.
+    fn generate<R: Rng>(rng: &mut R, size: usize) -> Generated<Self> {
+        let width = rng.gen_range(1..=40);
+        let density = rng.gen_range(0.0..1.0);
+        let rows: Vec<Vec<bool>> = (0..size.max(1))
+            .map(|_| (0..width).map(|_| rng.gen_bool(density)).collect())
+            .collect();
+
+        let trees = |right: usize, down: usize| {
+            (1..)
+                .map(|step| (step * down, step * right % width))
+                .take_while(|(row, _)| *row < rows.len())
+                .filter(|(row, col)| rows[*row][*col])
+                .count()
+        };
+
+        let input = rows
+            .iter()
+            .map(|row| {
+                let mut line: String = row.iter().map(|&t| if t { '#' } else { '.' }).collect();
+                line.push('\n');
+                line
+            })
+            .collect();
+
+        Generated {
+            input,
+            part_one: Some(trees(3, 1)),
+            part_two: Some(slopes().iter().map(|s| trees(s.right, s.down)).product()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aoc::rand::rngs::StdRng;
+    use aoc::rand::SeedableRng;
+    use aoc::Solution;
+
+    #[test]
+    fn test_generated_answers() {
+        for seed in 0..20 {
+            let generated = Map::generate(&mut StdRng::seed_from_u64(seed), 100);
+            let map = Map::parse(&generated.input).unwrap();
+            assert_eq!(generated.part_one, map.part_one().unwrap());
+            assert_eq!(generated.part_two, map.part_two().unwrap());
+        }
+    }
+}
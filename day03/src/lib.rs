@@ -0,0 +1,227 @@
+use aoc::serde_json::{json, Value};
+use aoc::{Error, ParseError, Solution};
+use std::fmt;
+
+aoc::embed_input!();
+
+mod generate;
+
+pub struct Slope {
+    pub right: usize,
+    pub down: usize,
+    pub trees: usize,
+}
+
+impl Slope {
+    pub fn new(right: usize, down: usize) -> Self {
+        assert!(right > 0 && down > 0);
+        Self {
+            right,
+            down,
+            trees: 0,
+        }
+    }
+
+    /// I had to break this down a bit. Ultimately, we are looking for the index of the target
+    /// character in our current line (with some modulo magic because the lines are infinitely
+    /// repeated rightward). We _could_ add together all the characters we've scanned over from
+    /// each iteration or we could do math (basic algebra).
+    ///
+    /// First, we skip any rows that we know are irrelevant (including the first row, since that's
+    /// where we start).
+    ///
+    /// Then we need to figure out which relevant row we're on: `row / self.down`. This is a factor
+    /// we can multiply by `self.right` to figure out how far we need to look in our current line to
+    /// get the target character. If down is `2`, then the first relevant row is `2` and
+    /// `2 / 2 = 1`, the second relevant row is `4` and `4 / 2 = 2`, and so on.
+    pub fn scan(&mut self, line: &str, row: usize) {
+        if row == 0 || row % self.down != 0 {
+            return;
+        }
+
+        if line
+            .chars()
+            .nth(((row / self.down) * self.right) % line.len())
+            .unwrap()
+            == '#'
+        {
+            self.trees += 1;
+        }
+    }
+}
+
+impl fmt::Display for Slope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "r{}d{}: {}", self.right, self.down, self.trees)
+    }
+}
+
+/// The slopes we're asked to check in part two.
+pub fn slopes() -> Vec<Slope> {
+    vec![
+        Slope::new(1, 1),
+        Slope::new(3, 1),
+        Slope::new(5, 1),
+        Slope::new(7, 1),
+        Slope::new(1, 2),
+    ]
+}
+
+/// The map of open squares (`.`) and trees (`#`), one row per line.
+pub struct Map {
+    pub rows: Vec<String>,
+}
+
+impl Map {
+    pub fn scan(&self, slopes: &mut [Slope]) {
+        for (row, line) in self.rows.iter().enumerate() {
+            for slope in slopes.iter_mut() {
+                slope.scan(line, row)
+            }
+        }
+    }
+}
+
+impl Solution for Map {
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> Result<Self, Error> {
+        let rows = aoc::parse_lines(input, |line| {
+            if line.is_empty() {
+                return Err(ParseError::new(line, 1, "empty row"));
+            }
+            if let Some(i) = line.find(|c| c != '.' && c != '#') {
+                return Err(ParseError::new(line, i + 1, "expected '.' or '#'"));
+            }
+            Ok(String::from(line))
+        })?;
+
+        Ok(Self { rows })
+    }
+
+    fn part_one(&self) -> Result<Option<usize>, Error> {
+        let mut slopes = [Slope::new(3, 1)];
+        self.scan(&mut slopes);
+        Ok(Some(slopes[0].trees))
+    }
+
+    fn part_two(&self) -> Result<Option<usize>, Error> {
+        let mut slopes = slopes();
+        self.scan(&mut slopes);
+        Ok(Some(slopes.iter().map(|s| s.trees).product()))
+    }
+
+    fn details(&self) -> Value {
+        let mut slopes = slopes();
+        self.scan(&mut slopes);
+
+        json!({
+            "rows": self.rows.len(),
+            "slopes": slopes
+                .iter()
+                .map(|s| json!({ "right": s.right, "down": s.down, "trees": s.trees }))
+                .collect::<Vec<_>>(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+    use proptest::string::string_regex;
+
+    fn sample() -> Vec<&'static str> {
+        vec![
+            "..##.......",
+            "#...#...#..",
+            ".#....#..#.",
+            "..#.#...#.#",
+            ".#...##..#.",
+            "..#.##.....",
+            ".#.#.#....#",
+            ".#........#",
+            "#.##...#...",
+            "#...##....#",
+            ".#..#...#.#",
+        ]
+    }
+
+    #[test]
+    fn test_r1d1() {
+        let mut s = Slope::new(1, 1);
+        for (row, line) in sample().iter().enumerate() {
+            s.scan(line, row)
+        }
+
+        assert_eq!(2, s.trees);
+    }
+
+    #[test]
+    fn test_r3d1() {
+        let mut s = Slope::new(3, 1);
+        for (row, line) in sample().iter().enumerate() {
+            s.scan(line, row)
+        }
+
+        assert_eq!(7, s.trees);
+    }
+
+    #[test]
+    fn test_r5d1() {
+        let mut s = Slope::new(5, 1);
+        for (row, line) in sample().iter().enumerate() {
+            s.scan(line, row)
+        }
+
+        assert_eq!(3, s.trees);
+    }
+
+    #[test]
+    fn test_r7d1() {
+        let mut s = Slope::new(7, 1);
+        for (row, line) in sample().iter().enumerate() {
+            s.scan(line, row)
+        }
+
+        assert_eq!(4, s.trees);
+    }
+
+    #[test]
+    fn test_r1d2() {
+        let mut s = Slope::new(1, 2);
+        for (row, line) in sample().iter().enumerate() {
+            s.scan(line, row)
+        }
+
+        assert_eq!(2, s.trees);
+    }
+
+    /// A map of equally wide rows.
+    fn map() -> impl Strategy<Value = Vec<String>> {
+        (1usize..20).prop_flat_map(|width| {
+            let row = string_regex(&format!("[.#]{{{}}}", width)).unwrap();
+            vec(row, 1..60)
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn prop_scan(rows in map(), right in 1usize..10, down in 1usize..4) {
+            let mut s = Slope::new(right, down);
+            for (row, line) in rows.iter().enumerate() {
+                s.scan(line, row)
+            }
+
+            let width = rows[0].len();
+            let walked = (1..)
+                .map(|step| (step * down, step * right % width))
+                .take_while(|(row, _)| *row < rows.len())
+                .filter(|(row, col)| rows[*row].as_bytes()[*col] == b'#')
+                .count();
+            prop_assert_eq!(walked, s.trees);
+        }
+    }
+}
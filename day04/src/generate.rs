@@ -0,0 +1,130 @@
+use crate::Batch;
+use aoc::rand::seq::SliceRandom;
+use aoc::rand::Rng;
+use aoc::{Generate, Generated};
+
+const KEYS: [&str; 7] = ["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid"];
+const EYE_COLORS: [&str; 7] = ["amb", "blu", "brn", "gry", "grn", "hzl", "oth"];
+
+fn digits<R: Rng>(rng: &mut R, n: usize) -> String {
+    (0..n)
+        .map(|_| (b'0' + rng.gen_range(0..10)) as char)
+        .collect()
+}
+
+fn hex<R: Rng>(rng: &mut R) -> String {
+    format!("#{:06x}", rng.gen_range(0..=0xff_ffff))
+}
+
+fn valid_value<R: Rng>(rng: &mut R, key: &str) -> String {
+    match key {
+        "byr" => rng.gen_range(1920..=2002).to_string(),
+        "iyr" => rng.gen_range(2010..=2020).to_string(),
+        "eyr" => rng.gen_range(2020..=2030).to_string(),
+        "hgt" if rng.gen() => format!("{}cm", rng.gen_range(150..=193)),
+        "hgt" => format!("{}in", rng.gen_range(59..=76)),
+        "hcl" => hex(rng),
+        "ecl" => String::from(*EYE_COLORS.choose(rng).unwrap()),
+        _ => digits(rng, 9),
+    }
+}
+
+fn invalid_value<R: Rng>(rng: &mut R, key: &str) -> String {
+    match key {
+        "byr" => rng.gen_range(2003..=2099).to_string(),
+        "iyr" => rng.gen_range(1990..=2009).to_string(),
+        "eyr" => rng.gen_range(2031..=2099).to_string(),
+        "hgt" if rng.gen() => format!("{}in", rng.gen_range(150..=193)),
+        "hgt" => rng.gen_range(59..=193).to_string(),
+        "hcl" if rng.gen() => hex(rng).replace('#', ""),
+        "hcl" => format!("{}z", &hex(rng)[..6]),
+        "ecl" => String::from(*["xry", "wat", "zzz"].choose(rng).unwrap()),
+        _ if rng.gen() => digits(rng, 8),
+        _ => digits(rng, 10),
+    }
+}
+
+impl Generate for Batch {
+    /// Each passport is built to be valid, to have every field but some invalid values, or to be
+    /// missing fields, with its fields shuffled across a few lines.
+    fn generate<R: Rng>(rng: &mut R, size: usize) -> Generated<Self> {
+        let mut passports = Vec::new();
+        let mut sufficient = 0;
+        let mut valid = 0;
+
+        for _ in 0..size.max(1) {
+            let mut keys = KEYS.to_vec();
+            keys.shuffle(rng);
+
+            let mut fields: Vec<String> = match rng.gen_range(0..3) {
+                0 => {
+                    let missing = rng.gen_range(1..=3);
+                    keys[missing..]
+                        .iter()
+                        .map(|k| format!("{}:{}", k, valid_value(rng, k)))
+                        .collect()
+                }
+                1 => {
+                    sufficient += 1;
+                    let invalid = rng.gen_range(1..=2);
+                    keys.iter()
+                        .enumerate()
+                        .map(|(i, k)| {
+                            let v = if i < invalid {
+                                invalid_value(rng, k)
+                            } else {
+                                valid_value(rng, k)
+                            };
+                            format!("{}:{}", k, v)
+                        })
+                        .collect()
+                }
+                _ => {
+                    sufficient += 1;
+                    valid += 1;
+                    keys.iter()
+                        .map(|k| format!("{}:{}", k, valid_value(rng, k)))
+                        .collect()
+                }
+            };
+
+            if rng.gen() {
+                fields.push(format!("cid:{}", rng.gen_range(1..1000)));
+            }
+            fields.shuffle(rng);
+
+            let lines: Vec<String> = fields
+                .chunks(rng.gen_range(1..=fields.len()))
+                .map(|chunk| chunk.join(" "))
+                .collect();
+            passports.push(lines.join("\n"));
+        }
+
+        let mut input = passports.join("\n\n");
+        input.push('\n');
+
+        Generated {
+            input,
+            part_one: Some(sufficient),
+            part_two: Some(valid),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aoc::rand::rngs::StdRng;
+    use aoc::rand::SeedableRng;
+    use aoc::Solution;
+
+    #[test]
+    fn test_generated_answers() {
+        for seed in 0..20 {
+            let generated = Batch::generate(&mut StdRng::seed_from_u64(seed), 100);
+            let batch = Batch::parse(&generated.input).unwrap();
+            assert_eq!(generated.part_one, batch.part_one().unwrap());
+            assert_eq!(generated.part_two, batch.part_two().unwrap());
+        }
+    }
+}
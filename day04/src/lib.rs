@@ -0,0 +1,464 @@
+use aoc::serde_json::{json, Value};
+use aoc::{Error, ParseError, Solution};
+use std::collections::BTreeMap;
+use std::fmt;
+
+aoc::embed_input!();
+
+mod generate;
+
+#[derive(Default)]
+pub struct Passport {
+    pub entries: BTreeMap<String, String>, // ordered for Display
+}
+
+impl Passport {
+    pub fn new() -> Self {
+        Self {
+            entries: BTreeMap::new(),
+        }
+    }
+
+    pub fn add_entries_from_line(&mut self, line: &str) -> Result<(), ParseError> {
+        for entry in line.split(' ').filter(|e| !e.is_empty()) {
+            self.add_entry(entry)
+                .map_err(|e| ParseError::at(line, entry, e.message))?;
+        }
+        Ok(())
+    }
+
+    fn add_entry(&mut self, entry: &str) -> Result<(), ParseError> {
+        let mut parts = entry.split(':');
+        let key = parts
+            .next()
+            .ok_or_else(|| ParseError::new(entry, 1, "invalid entry key format"))?;
+        let val = parts
+            .next()
+            .ok_or_else(|| ParseError::new(entry, 1, "invalid entry val format"))?;
+
+        if !Self::valid_required_key(key) {
+            // just skipping invalid keys (including "cid")
+            return Ok(());
+        }
+
+        self.entries.insert(String::from(key), String::from(val));
+        Ok(())
+    }
+
+    fn valid_required_key(key: &str) -> bool {
+        matches!(key, "byr" | "iyr" | "eyr" | "hgt" | "hcl" | "ecl" | "pid")
+    }
+
+    pub fn has_sufficient_entries(&self) -> bool {
+        self.entries.len() == 7
+    }
+
+    pub fn has_valid_entries(&self) -> bool {
+        self.has_sufficient_entries() && self.all_entries_valid()
+    }
+
+    fn all_entries_valid(&self) -> bool {
+        self.entries.iter().all(|(k, v)| match k.as_str() {
+            "byr" => v
+                .parse::<usize>()
+                .map(|y| (1920..=2002).contains(&y))
+                .unwrap_or(false),
+            "iyr" => v
+                .parse::<usize>()
+                .map(|y| (2010..=2020).contains(&y))
+                .unwrap_or(false),
+            "eyr" => v
+                .parse::<usize>()
+                .map(|y| (2020..=2030).contains(&y))
+                .unwrap_or(false),
+            "hgt" => {
+                if let Some(hstr) = v.strip_suffix("cm") {
+                    return hstr
+                        .parse::<usize>()
+                        .map(|h| (150..=193).contains(&h))
+                        .unwrap_or(false);
+                } else if let Some(hstr) = v.strip_suffix("in") {
+                    return hstr
+                        .parse::<usize>()
+                        .map(|h| (59..=76).contains(&h))
+                        .unwrap_or(false);
+                }
+                false
+            }
+            "hcl" => v
+                .strip_prefix("#")
+                .and_then(|c| {
+                    if c.chars()
+                        .all(|ch| ch.is_ascii_digit() || ch.is_ascii_lowercase())
+                    {
+                        return u32::from_str_radix(c, 16).ok();
+                    }
+                    None
+                })
+                .map(|b| b <= 16777215)
+                .unwrap_or(false),
+            "ecl" => matches!(
+                v.as_str(),
+                "amb" | "blu" | "brn" | "gry" | "grn" | "hzl" | "oth"
+            ),
+            "pid" => v.len() == 9 && v.chars().all(|c| c.is_ascii_digit()),
+            _ => false,
+        })
+    }
+}
+
+impl Passport {
+    pub fn status(&self) -> &'static str {
+        if self.has_valid_entries() {
+            "valid"
+        } else if self.has_sufficient_entries() {
+            "sufficient"
+        } else {
+            "invalid"
+        }
+    }
+}
+
+impl fmt::Display for Passport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let status = format!("{}:", self.status());
+        write!(
+            f,
+            "{:<11} ({}) {:?}",
+            status,
+            self.entries.len(),
+            self.entries
+        )
+    }
+}
+
+/// A batch of passports, separated from each other by blank lines.
+pub struct Batch {
+    pub passports: Vec<Passport>,
+}
+
+impl Batch {
+    pub fn total(&self) -> usize {
+        self.passports.len()
+    }
+}
+
+impl Solution for Batch {
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> Result<Self, Error> {
+        let mut passports = Vec::new();
+        let mut cur_passport = Passport::new();
+        let mut errors = Vec::new();
+
+        for (i, line) in input.lines().enumerate() {
+            if line.is_empty() {
+                passports.push(cur_passport);
+                cur_passport = Passport::new();
+            }
+
+            if let Err(e) = cur_passport.add_entries_from_line(line) {
+                errors.push(e.at_line(i + 1));
+            }
+        }
+
+        // don't forget the last one!
+        passports.push(cur_passport);
+
+        if !errors.is_empty() {
+            return Err(Error::Parse(errors));
+        }
+        Ok(Self { passports })
+    }
+
+    fn part_one(&self) -> Result<Option<usize>, Error> {
+        Ok(Some(
+            self.passports
+                .iter()
+                .filter(|p| p.has_sufficient_entries())
+                .count(),
+        ))
+    }
+
+    fn part_two(&self) -> Result<Option<usize>, Error> {
+        Ok(Some(
+            self.passports
+                .iter()
+                .filter(|p| p.has_valid_entries())
+                .count(),
+        ))
+    }
+
+    fn details(&self) -> Value {
+        json!({
+            "passports": self
+                .passports
+                .iter()
+                .map(|p| json!({ "status": p.status(), "entries": p.entries }))
+                .collect::<Vec<_>>(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use proptest::prelude::*;
+    use proptest::sample::subsequence;
+
+    fn validate_entry(entry: &str) -> bool {
+        let mut p = Passport::new();
+        p.add_entry(entry).unwrap();
+        p.entries.len() == 1 && p.all_entries_valid()
+    }
+
+    fn sample_1() -> Vec<&'static str> {
+        vec![
+            "ecl:gry pid:860033327 eyr:2020 hcl:#fffffd",
+            "byr:1937 iyr:2017 cid:147 hgt:183cm",
+        ]
+    }
+
+    fn sample_2() -> Vec<&'static str> {
+        vec![
+            "iyr:2013 ecl:amb cid:350 eyr:2023 pid:028048884",
+            "hcl:#cfa07d byr:1929",
+        ]
+    }
+
+    fn sample_3() -> Vec<&'static str> {
+        vec![
+            "hcl:#ae17e1 iyr:2013",
+            "eyr:2024",
+            "ecl:brn pid:760753108 byr:1931",
+            "hgt:179cm",
+        ]
+    }
+
+    fn sample_4() -> Vec<&'static str> {
+        vec![
+            "hcl:#cfa07d eyr:2025 pid:166559648",
+            "iyr:2011 ecl:brn hgt:59in",
+        ]
+    }
+
+    fn from_sample(sample: Vec<&str>) -> Passport {
+        let mut cur_passport = Passport::new();
+        for line in sample {
+            cur_passport.add_entries_from_line(line).unwrap()
+        }
+        println!("{}", cur_passport);
+        cur_passport
+    }
+
+    #[test]
+    fn test_sample_1() {
+        let p = from_sample(sample_1());
+        assert!(p.has_sufficient_entries())
+    }
+
+    #[test]
+    fn test_sample_2() {
+        let p = from_sample(sample_2());
+        assert!(!p.has_sufficient_entries())
+    }
+
+    #[test]
+    fn test_sample_3() {
+        let p = from_sample(sample_3());
+        assert!(p.has_sufficient_entries())
+    }
+
+    #[test]
+    fn test_sample_4() {
+        let p = from_sample(sample_4());
+        assert!(!p.has_sufficient_entries())
+    }
+
+    #[test]
+    fn test_add_entry() {
+        let mut p = Passport::new();
+        p.add_entry("byr:1937").unwrap();
+        p.add_entry("iyr:2017").unwrap();
+        p.add_entry("oops:uhoh").unwrap();
+        assert_eq!(p.entries.len(), 2);
+        assert!(!p.has_sufficient_entries());
+    }
+
+    #[test]
+    fn test_valid() {
+        let mut p = Passport::new();
+        p.add_entry("byr:1937").unwrap();
+        p.add_entry("iyr:2017").unwrap();
+        p.add_entry("ecl:gry").unwrap();
+        p.add_entry("pid:860033327").unwrap();
+        p.add_entry("eyr:2020").unwrap();
+        p.add_entry("hcl:#fffffd").unwrap();
+        p.add_entry("hgt:183cm").unwrap();
+        p.add_entry("cid:147").unwrap();
+        assert!(p.has_sufficient_entries());
+    }
+
+    #[test]
+    fn test_invalid() {
+        let mut p = Passport::new();
+        p.add_entry("byr:1937").unwrap();
+        p.add_entry("iyr:2017").unwrap();
+        p.add_entry("ecl:gry").unwrap();
+        p.add_entry("pid:860033327").unwrap();
+        p.add_entry("eyr:2020").unwrap();
+        p.add_entry("hcl:#fffffd").unwrap();
+        // p.add_entry("hgt:183cm").unwrap();
+        p.add_entry("cid:147").unwrap();
+        assert!(!p.has_sufficient_entries());
+    }
+
+    #[test]
+    fn test_valid_no_cid() {
+        let mut p = Passport::new();
+        p.add_entry("byr:1937").unwrap();
+        p.add_entry("iyr:2017").unwrap();
+        p.add_entry("ecl:gry").unwrap();
+        p.add_entry("pid:860033327").unwrap();
+        p.add_entry("eyr:2020").unwrap();
+        p.add_entry("hcl:#fffffd").unwrap();
+        p.add_entry("hgt:183cm").unwrap();
+        // p.add_entry("cid:147").unwrap();
+        assert!(p.has_sufficient_entries());
+    }
+
+    #[test]
+    fn test_invalid_no_cid() {
+        let mut p = Passport::new();
+        p.add_entry("byr:1937").unwrap();
+        p.add_entry("iyr:2017").unwrap();
+        p.add_entry("ecl:gry").unwrap();
+        p.add_entry("pid:860033327").unwrap();
+        p.add_entry("eyr:2020").unwrap();
+        p.add_entry("hcl:#fffffd").unwrap();
+        // p.add_entry("hgt:183cm").unwrap();
+        // p.add_entry("cid:147").unwrap();
+        assert!(!p.has_sufficient_entries());
+    }
+
+    #[test]
+    fn test_validation_byr() {
+        assert!(validate_entry("byr:2002"));
+        assert!(!validate_entry("byr:2002x"));
+        assert!(!validate_entry("byr:x2002"));
+        assert!(!validate_entry("byr:2003"));
+        assert!(!validate_entry("byr:19999"));
+        assert!(validate_entry("byr:1920"));
+        assert!(!validate_entry("byr:1919"));
+    }
+
+    #[test]
+    fn test_validation_iyr() {
+        assert!(validate_entry("iyr:2010"));
+        assert!(validate_entry("iyr:2020"));
+        assert!(!validate_entry("iyr:2021"));
+        assert!(!validate_entry("iyr:202*"));
+        assert!(!validate_entry("iyr:2009"));
+        assert!(!validate_entry("iyr:20009"));
+    }
+
+    #[test]
+    fn test_validation_eyr() {
+        assert!(validate_entry("eyr:2020"));
+        assert!(validate_entry("eyr:2030"));
+        assert!(!validate_entry("eyr:20300"));
+        assert!(!validate_entry("eyr:"));
+        // assert!(!validate_entry("eyr")); // not an issue with this dataset
+        assert!(!validate_entry("eyr:2031"));
+        assert!(!validate_entry("eyr:2019"));
+    }
+
+    #[test]
+    fn test_validation_hgt() {
+        assert!(validate_entry("hgt:60in"));
+        assert!(validate_entry("hgt:190cm"));
+        assert!(!validate_entry("hgt:190in"));
+        assert!(!validate_entry("hgt:190"));
+    }
+
+    #[test]
+    fn test_validation_hcl() {
+        assert!(validate_entry("hcl:#123abc"));
+        assert!(!validate_entry("hcl:#123abz"));
+        assert!(!validate_entry("hcl:123abc"));
+        assert!(validate_entry("hcl:#0525bb"));
+        assert!(!validate_entry("hcl:#0525bB"));
+        assert!(validate_entry("hcl:#ffffff"));
+        assert!(!validate_entry("hcl:#fffffg"));
+    }
+
+    #[test]
+    fn test_validation_ecl() {
+        assert!(validate_entry("ecl:amb"));
+        assert!(validate_entry("ecl:blu"));
+        assert!(validate_entry("ecl:brn"));
+        assert!(validate_entry("ecl:gry"));
+        assert!(validate_entry("ecl:grn"));
+        assert!(validate_entry("ecl:hzl"));
+        assert!(validate_entry("ecl:oth"));
+        assert!(!validate_entry("ecl:other"));
+        assert!(!validate_entry("ecl: blu"));
+    }
+
+    #[test]
+    fn test_validation_pid() {
+        assert!(validate_entry("pid:000000001"));
+        assert!(!validate_entry("pid:0123456789"));
+    }
+
+    #[test]
+    fn test_entry_without_colon() {
+        let mut p = Passport::new();
+        let e = p.add_entries_from_line("byr:1937 iyr2017").unwrap_err();
+        assert_eq!(10, e.column);
+        assert_eq!("byr:1937 iyr2017", e.text);
+    }
+
+    const KEYS: [&str; 8] = ["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid", "cid"];
+
+    proptest! {
+        #[test]
+        fn prop_sufficient(keys in subsequence(KEYS.to_vec(), 0..=8), val in "[a-z0-9#]{1,8}") {
+            let line = keys
+                .iter()
+                .map(|k| format!("{}:{}", k, val))
+                .collect::<Vec<_>>()
+                .join(" ");
+            let p = from_sample(vec![line.as_str()]);
+
+            let required = keys.iter().filter(|&&k| k != "cid").count();
+            prop_assert_eq!(required == 7, p.has_sufficient_entries());
+        }
+
+        #[test]
+        fn prop_years(year in 1900usize..2100) {
+            prop_assert_eq!((1920..=2002).contains(&year), validate_entry(&format!("byr:{}", year)));
+            prop_assert_eq!((2010..=2020).contains(&year), validate_entry(&format!("iyr:{}", year)));
+            prop_assert_eq!((2020..=2030).contains(&year), validate_entry(&format!("eyr:{}", year)));
+        }
+
+        #[test]
+        fn prop_hgt(height in 0usize..250) {
+            prop_assert_eq!((150..=193).contains(&height), validate_entry(&format!("hgt:{}cm", height)));
+            prop_assert_eq!((59..=76).contains(&height), validate_entry(&format!("hgt:{}in", height)));
+            let unitless = format!("hgt:{}", height);
+            prop_assert!(!validate_entry(&unitless));
+        }
+
+        #[test]
+        fn prop_batch_size(sizes in proptest::collection::vec(1usize..4, 1..20)) {
+            let passports: Vec<String> = sizes
+                .iter()
+                .map(|&n| vec!["byr:1937"; n].join(" "))
+                .collect();
+            let batch = Batch::parse(&passports.join("\n\n")).unwrap();
+            prop_assert_eq!(sizes.len(), batch.total());
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use crate::{encode, BoardingPasses};
+use aoc::rand::seq::SliceRandom;
+use aoc::rand::Rng;
+use aoc::{Generate, Generated};
+
+impl Generate for BoardingPasses {
+    /// A full run of seats somewhere on the plane with exactly one seat (ours) missing from the
+    /// middle, in random order. There are only 1024 seats, so `size` is capped to fit.
+    fn generate<R: Rng>(rng: &mut R, size: usize) -> Generated<Self> {
+        let len = size.clamp(3, 1000) as u16;
+        let lo = rng.gen_range(0..1024 - len);
+        let hi = lo + len - 1;
+        let ours = rng.gen_range(lo + 1..hi);
+
+        let mut seats: Vec<u16> = (lo..=hi).filter(|&id| id != ours).collect();
+        seats.shuffle(rng);
+
+        let input = seats.iter().map(|&id| encode(id) + "\n").collect();
+
+        Generated {
+            input,
+            part_one: Some(hi),
+            part_two: Some(ours),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aoc::rand::rngs::StdRng;
+    use aoc::rand::SeedableRng;
+    use aoc::Solution;
+
+    #[test]
+    fn test_generated_answers() {
+        for seed in 0..20 {
+            let generated = BoardingPasses::generate(&mut StdRng::seed_from_u64(seed), 500);
+            let passes = BoardingPasses::parse(&generated.input).unwrap();
+            assert_eq!(generated.part_one, passes.part_one().unwrap());
+            assert_eq!(generated.part_two, passes.part_two().unwrap());
+        }
+    }
+}
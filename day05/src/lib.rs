@@ -0,0 +1,171 @@
+use aoc::serde_json::{json, Value};
+use aoc::{Error, ParseError, Solution};
+use std::collections::BTreeSet;
+
+aoc::embed_input!();
+
+mod generate;
+
+pub fn seat_id(row: u8, col: u8) -> u16 {
+    (row as u16 * 8) + col as u16
+}
+
+pub fn find_row(str: &str) -> u8 {
+    let mut bin = str.replace("F", "0");
+    bin = bin.replace("B", "1");
+    u8::from_str_radix(bin.as_str(), 2).expect("failed to parse row as u8")
+}
+
+pub fn find_col(str: &str) -> u8 {
+    let mut bin = str.replace("L", "0");
+    bin = bin.replace("R", "1");
+    u8::from_str_radix(bin.as_str(), 2).expect("failed to parse col as u8")
+}
+
+/// Decodes a boarding pass like `FBFBBFFRLR` into its seat id.
+pub fn decode(line: &str) -> Result<u16, ParseError> {
+    for (i, c) in line.char_indices() {
+        let valid = if i < 7 {
+            c == 'F' || c == 'B'
+        } else {
+            c == 'L' || c == 'R'
+        };
+        if !valid {
+            let expected = if i < 7 { "'F' or 'B'" } else { "'L' or 'R'" };
+            return Err(ParseError::new(
+                line,
+                i + 1,
+                format!("expected {}", expected),
+            ));
+        }
+    }
+    if line.len() != 10 {
+        return Err(ParseError::new(
+            line,
+            line.len().min(10) + 1,
+            "expected exactly 10 characters",
+        ));
+    }
+
+    let (row_str, col_str) = line.split_at(7);
+    Ok(seat_id(find_row(row_str), find_col(col_str)))
+}
+
+/// The inverse of `decode`: writes the seat id out as row and column letters.
+pub fn encode(seat_id: u16) -> String {
+    let bits = |n: u16, width: u32, zero: char, one: char| -> String {
+        (0..width)
+            .rev()
+            .map(|i| if n >> i & 1 == 1 { one } else { zero })
+            .collect()
+    };
+    bits(seat_id / 8, 7, 'F', 'B') + &bits(seat_id % 8, 3, 'L', 'R')
+}
+
+/// Every seat id on the plane that has a boarding pass, in order.
+pub struct BoardingPasses {
+    pub seat_ids: BTreeSet<u16>,
+}
+
+impl BoardingPasses {
+    /// Lists the seat ids that fall in a gap between two boarding passes.
+    pub fn missing(&self) -> Vec<u16> {
+        let mut missing = Vec::new();
+        let mut iter = self.seat_ids.iter().peekable();
+
+        while let Some(seat_id) = iter.next() {
+            if let Some(&&next) = iter.peek() {
+                if next - 1 != *seat_id {
+                    missing.push(next - 1);
+                }
+            }
+        }
+        missing
+    }
+}
+
+impl Solution for BoardingPasses {
+    type PartOne = u16;
+    type PartTwo = u16;
+
+    fn parse(input: &str) -> Result<Self, Error> {
+        let seat_ids = aoc::parse_lines(input, decode)?.into_iter().collect();
+
+        Ok(Self { seat_ids })
+    }
+
+    fn part_one(&self) -> Result<Option<u16>, Error> {
+        Ok(self.seat_ids.iter().next_back().copied())
+    }
+
+    /// Our seat is the one missing from the list whose neighbours are both taken.
+    fn part_two(&self) -> Result<Option<u16>, Error> {
+        Ok(self
+            .missing()
+            .into_iter()
+            .find(|id| self.seat_ids.contains(&(id - 1)) && self.seat_ids.contains(&(id + 1))))
+    }
+
+    fn details(&self) -> Value {
+        json!({ "passes": self.seat_ids.len(), "missing": self.missing() })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_seat_id() {
+        assert_eq!(357, seat_id(44, 5));
+    }
+
+    #[test]
+    fn test_find_row() {
+        assert_eq!(44, find_row("FBFBBFF"));
+        assert_eq!(70, find_row("BFFFBBF"));
+        assert_eq!(14, find_row("FFFBBBF"));
+        assert_eq!(102, find_row("BBFFBBF"));
+        assert_eq!(0, find_row("FFFFFFF"));
+        assert_eq!(127, find_row("BBBBBBB"));
+    }
+
+    #[test]
+    fn test_find_col() {
+        assert_eq!(5, find_col("RLR"));
+        assert_eq!(7, find_col("RRR"));
+        assert_eq!(4, find_col("RLL"));
+    }
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(357, decode("FBFBBFFRLR").unwrap());
+        assert_eq!(3, decode("FBRBBFF").unwrap_err().column);
+        assert_eq!(8, decode("FBFBBFF").unwrap_err().column);
+        assert_eq!(11, decode("FBFBBFFRLRL").unwrap_err().column);
+    }
+
+    #[test]
+    fn test_encode() {
+        assert_eq!("FBFBBFFRLR", encode(357));
+    }
+
+    proptest! {
+        #[test]
+        fn prop_round_trip(seat_id in 0u16..1024) {
+            let pass = encode(seat_id);
+            prop_assert_eq!(seat_id / 8, find_row(&pass[..7]) as u16);
+            prop_assert_eq!(seat_id % 8, find_col(&pass[7..]) as u16);
+            prop_assert_eq!(seat_id, decode(&pass).unwrap());
+        }
+
+        #[test]
+        fn prop_decode_never_panics(line in "[FBLRX]{0,12}") {
+            let valid = line.len() == 10
+                && line[..7].chars().all(|c| c == 'F' || c == 'B')
+                && line[7..].chars().all(|c| c == 'L' || c == 'R');
+            prop_assert_eq!(valid, decode(&line).is_ok());
+        }
+    }
+}
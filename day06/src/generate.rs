@@ -0,0 +1,61 @@
+use crate::Declarations;
+use aoc::rand::Rng;
+use aoc::{Generate, Generated};
+
+impl Generate for Declarations {
+    /// `size` groups of one to five people, each answering "yes" to a random set of questions.
+    /// Answers are tracked as bitmasks so the expected counts don't share any code with the
+    /// solver.
+    fn generate<R: Rng>(rng: &mut R, size: usize) -> Generated<Self> {
+        let mut groups = Vec::new();
+        let mut anyone = 0;
+        let mut everyone = 0;
+
+        for _ in 0..size.max(1) {
+            let people: Vec<u32> = (0..rng.gen_range(1..=5))
+                .map(|_| rng.gen_range(1..1 << 26))
+                .collect();
+
+            anyone += people.iter().fold(0, |a, p| a | p).count_ones() as usize;
+            everyone += people.iter().fold(!0, |a, p| a & p).count_ones() as usize;
+
+            let lines: Vec<String> = people
+                .iter()
+                .map(|p| {
+                    (0..26)
+                        .filter(|i| p >> i & 1 == 1)
+                        .map(|i| (b'a' + i as u8) as char)
+                        .collect()
+                })
+                .collect();
+            groups.push(lines.join("\n"));
+        }
+
+        let mut input = groups.join("\n\n");
+        input.push('\n');
+
+        Generated {
+            input,
+            part_one: Some(anyone),
+            part_two: Some(everyone),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aoc::rand::rngs::StdRng;
+    use aoc::rand::SeedableRng;
+    use aoc::Solution;
+
+    #[test]
+    fn test_generated_answers() {
+        for seed in 0..20 {
+            let generated = Declarations::generate(&mut StdRng::seed_from_u64(seed), 100);
+            let declarations = Declarations::parse(&generated.input).unwrap();
+            assert_eq!(generated.part_one, declarations.part_one().unwrap());
+            assert_eq!(generated.part_two, declarations.part_two().unwrap());
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use aoc::serde_json::{json, Value};
+use aoc::{Error, ParseError, Solution};
+use std::collections::HashSet;
+
+aoc::embed_input!();
+
+mod generate;
+
+/// The customs declaration forms, one group per blank-line separated block.
+pub struct Declarations {
+    pub groups: Vec<String>,
+}
+
+/// Counts the questions anyone in the group answered "yes" to.
+pub fn anyone(group: &str) -> usize {
+    let set: HashSet<char> = group.chars().filter(|c| !c.is_ascii_whitespace()).collect();
+    set.len()
+}
+
+/// Counts the questions everyone in the group answered "yes" to.
+pub fn everyone(group: &str) -> usize {
+    let mut sets = group.lines().map(|l| l.chars().collect::<HashSet<char>>());
+    let first: HashSet<char> = match sets.next() {
+        Some(first) => first,
+        None => return 0,
+    };
+    let set: HashSet<char> = sets.fold(first, |a, b| a.intersection(&b).cloned().collect());
+    set.len()
+}
+
+impl Solution for Declarations {
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> Result<Self, Error> {
+        let mut groups = vec![String::new()];
+        let mut errors = Vec::new();
+
+        for (i, line) in input.lines().enumerate() {
+            if line.is_empty() {
+                groups.push(String::new());
+                continue;
+            }
+
+            if let Some(col) = line.find(|c: char| !c.is_ascii_lowercase()) {
+                errors.push(
+                    ParseError::new(line, col + 1, "expected a question from 'a' to 'z'")
+                        .at_line(i + 1),
+                );
+            }
+
+            let group = groups.last_mut().unwrap();
+            group.push_str(line);
+            group.push('\n');
+        }
+
+        if !errors.is_empty() {
+            return Err(Error::Parse(errors));
+        }
+        Ok(Self { groups })
+    }
+
+    fn part_one(&self) -> Result<Option<usize>, Error> {
+        Ok(Some(self.groups.iter().map(|g| anyone(g)).sum()))
+    }
+
+    fn part_two(&self) -> Result<Option<usize>, Error> {
+        Ok(Some(self.groups.iter().map(|g| everyone(g)).sum()))
+    }
+
+    fn details(&self) -> Value {
+        json!({ "groups": self.groups.len() })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    /// A group as one bitmask of answered questions per person.
+    fn group() -> impl Strategy<Value = Vec<u32>> {
+        vec(1u32..1 << 26, 1..6)
+    }
+
+    fn to_text(group: &[u32]) -> String {
+        let mut text = String::new();
+        for person in group {
+            text.extend(
+                (0..26)
+                    .filter(|i| person >> i & 1 == 1)
+                    .map(|i| (b'a' + i) as char),
+            );
+            text.push('\n');
+        }
+        text
+    }
+
+    #[test]
+    fn test_sample() {
+        let decl = Declarations::parse("abc\n\na\nb\nc\n\nab\nac\n\na\na\na\na\n\nb").unwrap();
+        assert_eq!(Some(11), decl.part_one().unwrap());
+        assert_eq!(Some(6), decl.part_two().unwrap());
+    }
+
+    proptest! {
+        #[test]
+        fn prop_anyone(group in group()) {
+            let union = group.iter().fold(0, |a, b| a | b);
+            prop_assert_eq!(union.count_ones() as usize, anyone(&to_text(&group)));
+        }
+
+        #[test]
+        fn prop_everyone(group in group()) {
+            let intersection = group.iter().fold(!0, |a, b| a & b);
+            prop_assert_eq!(intersection.count_ones() as usize, everyone(&to_text(&group)));
+        }
+
+        #[test]
+        fn prop_parse_groups(groups in vec(group(), 1..5)) {
+            let input = groups.iter().map(|g| to_text(g)).collect::<Vec<_>>().join("\n");
+            let decl = Declarations::parse(&input).unwrap();
+            prop_assert_eq!(groups.len(), decl.groups.len());
+            prop_assert!(decl.part_two().unwrap() <= decl.part_one().unwrap());
+        }
+    }
+}
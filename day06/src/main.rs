@@ -1,38 +1,18 @@
-use std::collections::HashSet;
-use std::io::Read;
-use std::{env, fs, io};
+use aoc::Solution;
+use day06::Declarations;
 
-fn main() -> io::Result<()> {
-    let filename = env::args().nth(1).expect("missing filename");
-
-    let f = fs::File::open(&filename)?;
-
-    let mut reader = io::BufReader::new(f);
-    let mut buf = String::new();
-    reader
-        .read_to_string(&mut buf)
-        .expect("failed to read contents to string");
-
-    let sum_anyone: usize = buf
-        .split("\n\n")
-        .map(|group| {
-            let set: HashSet<char> = group.chars().filter(|c| !c.is_ascii_whitespace()).collect();
-            set.len()
+fn main() {
+    aoc::main(|| {
+        aoc::for_each_input(day06::INPUT, |declarations: Declarations| {
+            println!(
+                "total questions anyone answered {}",
+                aoc::answer(declarations.part_one()?)
+            );
+            println!(
+                "total questions everyone answered {}",
+                aoc::answer(declarations.part_two()?)
+            );
+            Ok(())
         })
-        .sum();
-
-    let sum_everyone: usize = buf
-        .split("\n\n")
-        .map(|group| {
-            let mut sets = group.lines().map(|l| l.chars().collect::<HashSet<char>>());
-            let first: HashSet<char> = sets.next().expect("group has no members");
-            let set: HashSet<char> = sets.fold(first, |a, b| a.intersection(&b).cloned().collect());
-            set.len()
-        })
-        .sum();
-
-    println!("total questions anyone answered {}", sum_anyone);
-    println!("total questions everyone answered {}", sum_everyone);
-
-    Ok(())
+    })
 }
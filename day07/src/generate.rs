@@ -0,0 +1,120 @@
+use crate::{Rules, OUR_BAG};
+use aoc::rand::seq::{index, SliceRandom};
+use aoc::rand::Rng;
+use aoc::{Generate, Generated};
+
+const ADJECTIVES: [&str; 24] = [
+    "light", "dark", "bright", "muted", "shiny", "faded", "dotted", "vibrant", "pale", "wavy",
+    "dull", "posh", "clear", "drab", "dim", "mirrored", "plaid", "striped", "dusty", "vivid",
+    "hazy", "smooth", "glossy", "rough",
+];
+const COLORS: [&str; 24] = [
+    "red", "orange", "white", "yellow", "gold", "olive", "plum", "blue", "black", "green",
+    "violet", "indigo", "teal", "tan", "lime", "maroon", "cyan", "beige", "silver", "crimson",
+    "bronze", "fuchsia", "salmon", "coral",
+];
+
+/// How many bags can sit below ours. Each level can multiply the bags inside ours by up to 16,
+/// so this keeps part two's answer well within a `usize` however big the rest of the rule set
+/// gets.
+const BELOW_OURS: usize = 8;
+
+impl Generate for Rules {
+    /// An acyclic rule set: bags are put in a random order and may only contain bags later in
+    /// it. Both answers are worked out from back to front over that order. Past the 575 plain
+    /// names besides ours, adjectives get numbered (`dark2 red`) to make more.
+    fn generate<R: Rng>(rng: &mut R, size: usize) -> Generated<Self> {
+        let plain = |round: usize| {
+            ADJECTIVES.iter().flat_map(move |adj| {
+                COLORS.iter().map(move |color| match round {
+                    0 => format!("{} {}", adj, color),
+                    _ => format!("{}{} {}", adj, round + 1, color),
+                })
+            })
+        };
+        let mut names: Vec<String> = plain(0).filter(|name| name != OUR_BAG).collect();
+        names.shuffle(rng);
+
+        let n = size.max(1);
+        let mut round = 1;
+        while names.len() < n - 1 {
+            names.extend(plain(round));
+            round += 1;
+        }
+        names.truncate(n - 1);
+        let ours = n.saturating_sub(BELOW_OURS + 1);
+        names.insert(ours, String::from(OUR_BAG));
+
+        let mut children: Vec<Vec<(usize, usize)>> = vec![Vec::new(); n];
+        for (i, inner) in children.iter_mut().enumerate() {
+            let after = n - i - 1;
+            // make sure ours holds something whenever it can, so part two isn't trivially zero
+            let least = if i == ours { after.min(1) } else { 0 };
+            let count = rng.gen_range(least..=after.min(3));
+            for j in index::sample(rng, after, count) {
+                inner.push((i + 1 + j, rng.gen_range(1..=4)));
+            }
+        }
+
+        let mut holds_ours = vec![false; n];
+        let mut total = vec![0; n];
+        for i in (0..n).rev() {
+            holds_ours[i] = children[i].iter().any(|&(j, _)| j == ours || holds_ours[j]);
+            total[i] = children[i]
+                .iter()
+                .map(|&(j, qty)| qty * (1 + total[j]))
+                .sum();
+        }
+
+        let mut lines: Vec<String> = (0..n)
+            .map(|i| {
+                let contents = if children[i].is_empty() {
+                    String::from("no other bags")
+                } else {
+                    children[i]
+                        .iter()
+                        .map(|&(j, qty)| {
+                            let plural = if qty == 1 { "bag" } else { "bags" };
+                            format!("{} {} {}", qty, names[j], plural)
+                        })
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                };
+                format!("{} bags contain {}.\n", names[i], contents)
+            })
+            .collect();
+        lines.shuffle(rng);
+
+        Generated {
+            input: lines.concat(),
+            part_one: Some(holds_ours.iter().filter(|&&h| h).count()),
+            part_two: Some(total[ours]),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aoc::rand::rngs::StdRng;
+    use aoc::rand::SeedableRng;
+    use aoc::Solution;
+
+    #[test]
+    fn test_generated_answers() {
+        for seed in 0..20 {
+            let generated = Rules::generate(&mut StdRng::seed_from_u64(seed), 200);
+            let rules = Rules::parse(&generated.input).unwrap();
+            assert_eq!(generated.part_one, rules.part_one().unwrap());
+            assert_eq!(generated.part_two, rules.part_two().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_generated_size() {
+        let generated = Rules::generate(&mut StdRng::seed_from_u64(7), 2000);
+        let rules = Rules::parse(&generated.input).unwrap();
+        assert_eq!(2000, rules.0.len());
+        assert_eq!(generated.part_two, rules.part_two().unwrap());
+    }
+}
@@ -0,0 +1,389 @@
+use aoc::serde_json::{json, Value};
+use aoc::{Error, ParseError, Solution};
+use std::collections::{HashMap, HashSet};
+
+aoc::embed_input!();
+
+mod generate;
+
+/// The bag we're carrying around.
+pub const OUR_BAG: &str = "shiny gold";
+
+/// Structure holding all the bag rules
+///
+///```text
+/// outer => [(inner, qty), ..]
+/// ```
+#[derive(Default)]
+pub struct Rules(HashMap<String, HashMap<String, usize>>);
+
+/// Structure holding a lookup of which bags can contain which
+///
+/// ```text
+/// inner => [outer, ..]
+/// ```
+#[derive(Default)]
+pub struct OuterLookup(HashMap<String, HashSet<String>>);
+
+impl Rules {
+    pub fn from_lines(lines: Vec<&str>) -> Result<Rules, Error> {
+        let mut rules = Rules::default();
+        let mut errors = Vec::new();
+
+        for (i, line) in lines.into_iter().enumerate() {
+            if let Err(e) = rules.parse_rule(line) {
+                errors.push(e.at_line(i + 1));
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(Error::Parse(errors));
+        }
+        Ok(rules)
+    }
+
+    pub fn parse_rule(&mut self, line: &str) -> Result<(), ParseError> {
+        let missing =
+            |what: &str| ParseError::new(line, line.len() + 1, format!("missing {}", what));
+
+        let mut parts = line.split_ascii_whitespace();
+        let mut outer = String::from(parts.next().ok_or_else(|| missing("adj of bag name"))?);
+        outer.push(' ');
+        outer.push_str(parts.next().ok_or_else(|| missing("color of bag name"))?);
+
+        parts
+            .next()
+            .ok_or_else(|| missing("'bags' marker of bag"))?;
+        parts.next().ok_or_else(|| missing("'contains' marker"))?;
+
+        let rules = self.0.entry(outer).or_default();
+
+        while let Some(count) = parts.next() {
+            if count.starts_with("no") {
+                // should only happen on first iteration if it's gonna happen
+                break;
+            }
+
+            let qty = count
+                .parse::<usize>()
+                .map_err(|_| ParseError::at(line, count, "invalid quantity"))?;
+
+            let mut inner = String::from(parts.next().ok_or_else(|| missing("adj of child name"))?);
+            inner.push(' ');
+            inner.push_str(parts.next().ok_or_else(|| missing("color of child name"))?);
+
+            rules.insert(inner, qty);
+
+            parts
+                .next()
+                .ok_or_else(|| missing("'bags' marker of child"))?;
+        }
+        Ok(())
+    }
+
+    pub fn to_parent_lookup(&self) -> OuterLookup {
+        let mut lookup = OuterLookup::default();
+        for (outer, inners) in self.0.iter() {
+            for inner in inners.keys() {
+                let entry = lookup.0.entry(inner.clone()).or_default();
+                entry.insert(outer.clone());
+            }
+        }
+
+        lookup
+    }
+
+    /// How many bags `bag` makes, counting itself, remembering every bag it works out in `sums`.
+    /// `open` holds the bags being unpacked further up, so a bag that ends up inside itself is
+    /// caught rather than unpacked for ever.
+    fn sum_qtys<'a>(
+        &'a self,
+        bag: &'a str,
+        open: &mut HashSet<&'a str>,
+        sums: &mut HashMap<&'a str, usize>,
+    ) -> Result<usize, Error> {
+        if let Some(&sum) = sums.get(bag) {
+            return Ok(sum);
+        }
+        if !open.insert(bag) {
+            return Err(Error::Solve(format!(
+                "'{}' bags end up inside themselves",
+                bag
+            )));
+        }
+
+        let mut sum: usize = 1;
+        if let Some(rules) = self.0.get(bag) {
+            for (inner, &qty) in rules.iter() {
+                sum = self
+                    .sum_qtys(inner, open, sums)?
+                    .checked_mul(qty)
+                    .and_then(|inside| inside.checked_add(sum))
+                    .ok_or_else(|| {
+                        Error::Solve(format!("too many bags inside '{}' to count", bag))
+                    })?;
+            }
+        }
+
+        open.remove(bag);
+        sums.insert(bag, sum);
+        Ok(sum)
+    }
+
+    /// How many bags `start` has to contain, failing if it ends up inside itself or there are
+    /// too many to count.
+    pub fn count_required<'a>(&'a self, start: &'a str) -> Result<usize, Error> {
+        let sum = self.sum_qtys(start, &mut HashSet::new(), &mut HashMap::new())?;
+        Ok(sum - 1)
+    }
+}
+
+impl OuterLookup {
+    fn find_outers(&self, inner: &String, found: &mut HashSet<String>) {
+        if found.contains(inner) {
+            return;
+        }
+
+        found.insert(inner.clone());
+
+        if let Some(direct_outers) = self.0.get(inner) {
+            for outer in direct_outers {
+                self.find_outers(outer, found);
+            }
+        }
+    }
+
+    pub fn count(&self, inner: &str) -> usize {
+        let mut found = HashSet::default();
+        self.find_outers(&String::from(inner), &mut found);
+        found.len() - 1 // exclude initial child
+    }
+}
+
+impl Solution for Rules {
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> Result<Self, Error> {
+        Rules::from_lines(input.lines().collect())
+    }
+
+    fn part_one(&self) -> Result<Option<usize>, Error> {
+        Ok(Some(self.to_parent_lookup().count(OUR_BAG)))
+    }
+
+    fn part_two(&self) -> Result<Option<usize>, Error> {
+        self.count_required(OUR_BAG).map(Some)
+    }
+
+    fn details(&self) -> Value {
+        json!({ "rules": self.0.len() })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    fn sample1<'a>() -> Vec<&'a str> {
+        vec![
+            "light red bags contain 1 bright white bag, 2 muted yellow bags.",
+            "dark orange bags contain 3 bright white bags, 4 muted yellow bags.",
+            "bright white bags contain 1 shiny gold bag.",
+            "muted yellow bags contain 2 shiny gold bags, 9 faded blue bags.",
+            "shiny gold bags contain 1 dark olive bag, 2 vibrant plum bags.",
+            "dark olive bags contain 3 faded blue bags, 4 dotted black bags.",
+            "vibrant plum bags contain 5 faded blue bags, 6 dotted black bags.",
+            "faded blue bags contain no other bags.",
+            "dotted black bags contain no other bags.",
+        ]
+    }
+
+    fn sample2<'a>() -> Vec<&'a str> {
+        vec![
+            "shiny gold bags contain 2 dark red bags.",
+            "dark red bags contain 2 dark orange bags.",
+            "dark orange bags contain 2 dark yellow bags.",
+            "dark yellow bags contain 2 dark green bags.",
+            "dark green bags contain 2 dark blue bags.",
+            "dark blue bags contain 2 dark violet bags.",
+            "dark violet bags contain no other bags.",
+        ]
+    }
+
+    #[test]
+    fn test_cost() {
+        let rules = Rules::from_lines(sample1()).unwrap();
+        assert_eq!(32, rules.count_required("shiny gold").unwrap());
+    }
+    #[test]
+    fn test_cost_deep() {
+        let rules = Rules::from_lines(sample2()).unwrap();
+        assert_eq!(126, rules.count_required("shiny gold").unwrap());
+    }
+
+    #[test]
+    fn test_cost_cycle() {
+        let rules = Rules::from_lines(vec![
+            "shiny gold bags contain 2 dark red bags.",
+            "dark red bags contain 1 dark blue bag, 3 faded gray bags.",
+            "dark blue bags contain 1 dark red bag.",
+            "faded gray bags contain no other bags.",
+        ])
+        .unwrap();
+        match rules.count_required("shiny gold") {
+            Err(Error::Solve(e)) => assert!(e.ends_with("bags end up inside themselves"), "{}", e),
+            _ => panic!("expected a cycle"),
+        }
+        // bags outside the cycle are still fine
+        assert_eq!(0, rules.count_required("faded gray").unwrap());
+        assert_eq!(3, rules.to_parent_lookup().count("faded gray"));
+    }
+
+    #[test]
+    fn test_cost_overflow() {
+        let rules = Rules::from_lines(vec![
+            "shiny gold bags contain 4294967296 dark red bags.",
+            "dark red bags contain 4294967296 dark blue bags.",
+            "dark blue bags contain no other bags.",
+        ])
+        .unwrap();
+        match rules.count_required("shiny gold") {
+            Err(Error::Solve(e)) => assert_eq!("too many bags inside 'shiny gold' to count", e),
+            _ => panic!("expected an overflow"),
+        }
+        assert_eq!(4294967296, rules.count_required("dark red").unwrap());
+    }
+
+    #[test]
+    fn test_simple() {
+        let rules = Rules::from_lines(vec![
+            "sky blue bags contain 3 dark red bags, 1 mint green bag",
+        ])
+        .unwrap();
+        let lookup = rules.to_parent_lookup();
+
+        assert_eq!(1, lookup.count("dark red"));
+        assert_eq!(1, lookup.count("mint green"));
+        assert_eq!(0, lookup.count("sky blue"));
+    }
+
+    #[test]
+    fn test_deeper() {
+        let rules = Rules::from_lines(vec![
+            "sky blue bags contain 3 dark red bags, 1 mint green bag",
+            "dark red bags contain 2 ugly brown bags",
+            "ugly brown bags contain 4 dirty yellow bags",
+        ])
+        .unwrap();
+        let lookup = rules.to_parent_lookup();
+
+        assert_eq!(3, lookup.count("dirty yellow"));
+        assert_eq!(1, lookup.count("dark red"));
+        assert_eq!(1, lookup.count("mint green"));
+        assert_eq!(2, lookup.count("ugly brown"));
+    }
+
+    #[test]
+    fn test_cycles() {
+        let rules = Rules::from_lines(vec![
+            "sky blue bags contain 3 dark red bags, 1 mint green bag",
+            "dark red bags contain 2 ugly brown bags",
+            "ugly brown bags contain 4 dirty yellow bags",
+            "faded gray bags contains 4 dirty yellow bags",
+        ])
+        .unwrap();
+        let lookup = rules.to_parent_lookup();
+
+        assert_eq!(4, lookup.count("dirty yellow"));
+        assert_eq!(0, lookup.count("faded gray"));
+    }
+
+    #[test]
+    fn test_malformed() {
+        let e = match Rules::from_lines(vec![
+            "sky blue bags contain 3 dark red bags, 1 mint green bag",
+            "dark red bags contain two ugly brown bags",
+            "ugly brown",
+        ]) {
+            Err(Error::Parse(errors)) => errors,
+            _ => panic!("expected parse errors"),
+        };
+
+        assert_eq!(2, e.len());
+        assert_eq!((2, 23), (e[0].line, e[0].column));
+        assert_eq!((3, 11), (e[1].line, e[1].column));
+    }
+
+    /// Edges of a small acyclic graph as `(outer, inner, qty)`, always pointing at a later bag.
+    fn graph() -> impl Strategy<Value = (usize, Vec<(usize, usize, usize)>)> {
+        (2usize..7).prop_flat_map(|n| {
+            let edge =
+                (0..n - 1).prop_flat_map(move |outer| (Just(outer), outer + 1..n, 1usize..3));
+            (Just(n), vec(edge, 0..2 * n))
+        })
+    }
+
+    fn name(bag: usize) -> String {
+        format!("dark c{}", bag)
+    }
+
+    fn to_rules(n: usize, edges: &[(usize, usize, usize)]) -> Rules {
+        let lines: Vec<String> = (0..n)
+            .map(|outer| {
+                let mut inners: Vec<String> = edges
+                    .iter()
+                    .filter(|e| e.0 == outer)
+                    .map(|&(_, inner, qty)| format!("{} {} bags", qty, name(inner)))
+                    .collect();
+                if inners.is_empty() {
+                    inners.push(String::from("no other bags"));
+                }
+                format!("{} bags contain {}.", name(outer), inners.join(", "))
+            })
+            .collect();
+        Rules::from_lines(lines.iter().map(String::as_str).collect()).unwrap()
+    }
+
+    proptest! {
+        #[test]
+        fn prop_count_required((n, edges) in graph()) {
+            let rules = to_rules(n, &edges);
+
+            // unpack every bag one at a time
+            let mut stack = vec![name(0)];
+            let mut unpacked = 0;
+            while let Some(bag) = stack.pop() {
+                for (inner, qty) in rules.0[&bag].iter() {
+                    for _ in 0..*qty {
+                        stack.push(inner.clone());
+                    }
+                }
+                unpacked += 1;
+            }
+
+            prop_assert_eq!(unpacked - 1, rules.count_required(&name(0)).unwrap());
+        }
+
+        #[test]
+        fn prop_outer_count((n, edges) in graph()) {
+            let rules = to_rules(n, &edges);
+            let inner = name(n - 1);
+            let holds = |outer: &String| {
+                let mut stack = vec![outer.clone()];
+                let mut seen = HashSet::new();
+                while let Some(bag) = stack.pop() {
+                    if seen.insert(bag.clone()) {
+                        stack.extend(rules.0[&bag].keys().cloned());
+                    }
+                }
+                seen.contains(&inner)
+            };
+            let expected = rules.0.keys().filter(|&outer| *outer != inner && holds(outer)).count();
+
+            prop_assert_eq!(expected, rules.to_parent_lookup().count(&inner));
+        }
+    }
+}
@@ -0,0 +1,20 @@
+#![no_main]
+use day02::{PasswordEntry, Unit, POLICIES};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|line: &str| {
+    if let Ok(mut entry) = PasswordEntry::parse(line) {
+        // anything the hand-written parser takes without a rule, the regex it replaced reads
+        // the same way
+        if entry.rule().is_none() {
+            assert_eq!(Ok(entry), PasswordEntry::parse_regex(line).map_err(|_| ()));
+        }
+
+        for &unit in Unit::ALL.iter() {
+            entry.unit = unit;
+            for policy in POLICIES {
+                policy.check(&entry);
+            }
+        }
+    }
+});
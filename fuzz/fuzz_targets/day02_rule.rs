@@ -0,0 +1,15 @@
+#![no_main]
+use day02::{PasswordEntry, PasswordPolicy, Rule};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|text: &str| {
+    if let Ok(rule) = text.parse::<Rule>() {
+        // whatever parses has to read back as the same rule
+        let shown = rule.to_string();
+        let reparsed = shown.parse::<Rule>().expect("displayed rule should parse");
+        assert_eq!(shown, reparsed.to_string());
+
+        let entry = PasswordEntry::parse("1-3 a: aB3!é👍🏽").unwrap();
+        assert_eq!(rule.check(&entry), reparsed.check(&entry));
+    }
+});
@@ -0,0 +1,10 @@
+#![no_main]
+use day04::Passport;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|line: &str| {
+    let mut passport = Passport::new();
+    if passport.add_entries_from_line(line).is_ok() {
+        passport.status();
+    }
+});
@@ -0,0 +1,7 @@
+#![no_main]
+use aoc::Solution;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let _ = day06::Declarations::parse(input);
+});
@@ -0,0 +1,7 @@
+#![no_main]
+use day07::Rules;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|line: &str| {
+    let _ = Rules::default().parse_rule(line);
+});
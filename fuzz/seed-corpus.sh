@@ -0,0 +1,35 @@
+#!/bin/sh
+# Rebuilds the seed corpora from the committed puzzle inputs: whole files for the targets that
+# parse a full input, and the first lines of each file for the ones that parse a single line.
+set -e
+cd "$(dirname "$0")"
+
+LINES=32
+
+whole() {
+    mkdir -p "seeds/$1"
+    cp "../$2/input.txt" "seeds/$1/input.txt"
+}
+
+lines() {
+    mkdir -p "seeds/$1"
+    head -n "$LINES" "../$2/input.txt" | awk -v dir="seeds/$1" \
+        '{ file = sprintf("%s/line-%02d", dir, NR); printf "%s", $0 > file; close(file) }'
+}
+
+# the examples from day02's README, since there's no input file of rules
+rules() {
+    mkdir -p "seeds/$1"
+    printf '%s' 'count and not index' > "seeds/$1/count-not-index"
+    printf '%s' 'min-length 12 and has 1+ digit and forbid "password"' > "seeds/$1/corporate"
+    printf '%s' '(at 1 upper or has 2-3 "!?") and not max-length 4' > "seeds/$1/grouped"
+}
+
+whole day01_parse day01
+lines day02_password_entry day02
+rules day02_rule
+whole day03_parse day03
+lines day04_passport day04
+lines day05_decode day05
+whole day06_parse day06
+lines day07_parse_rule day07
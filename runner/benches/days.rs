@@ -0,0 +1,144 @@
+//! Times parsing separately from each part, for every day, on both the committed input and a
+//! generated one about ten times as big, plus day01's search strategies against each other and
+//! day02's line parsers.
+//!
+//! Save a baseline before trying a new approach and compare against it afterwards:
+//!
+//! ```text
+//! cargo bench -p runner --bench days -- --save-baseline before
+//! cargo bench -p runner --bench days -- --baseline before
+//! ```
+
+use aoc::rand::rngs::StdRng;
+use aoc::rand::{Rng, SeedableRng};
+use aoc::Generate;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use day01::Strategy;
+use day02::{PasswordDatabase, PasswordEntry};
+use std::fs;
+use std::path::Path;
+
+/// How many times as many records as the committed input the generated one has.
+const SCALE: usize = 10;
+
+fn read_input(path: &str) -> String {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+    fs::read_to_string(root.join(path)).expect("failed to read input")
+}
+
+/// Benchmarks a day on its committed input and on a generated one with `SCALE` times as many
+/// records, which are split by `separator` (days that group lines put a blank line between
+/// groups). Repeating the committed input instead would be no use: duplicate boarding passes and
+/// bag rules collapse into one. Day 5 can't go past the plane's 1024 seats, so its generated
+/// input stops at 1000 passes.
+fn bench_day<S: Generate>(c: &mut Criterion, name: &str, path: &str, separator: &str) {
+    let real = read_input(path);
+    let records = real.trim_end().split(separator).count();
+    let generated = S::generate(&mut StdRng::seed_from_u64(2020), SCALE * records).input;
+    let inputs = [("real", real), ("scaled", generated)];
+
+    let mut group = c.benchmark_group(name);
+    for (label, input) in inputs.iter() {
+        let solution = match S::parse(input) {
+            Ok(solution) => solution,
+            Err(e) => panic!("{} {} input failed to parse:\n{}", name, label, e),
+        };
+
+        group.bench_with_input(BenchmarkId::new("parse", label), input, |b, input| {
+            b.iter(|| S::parse(input).is_ok())
+        });
+        group.bench_with_input(BenchmarkId::new("part_one", label), &solution, |b, s| {
+            b.iter(|| s.part_one().is_ok())
+        });
+        group.bench_with_input(BenchmarkId::new("part_two", label), &solution, |b, s| {
+            b.iter(|| s.part_two().is_ok())
+        });
+    }
+    group.finish();
+}
+
+fn days(c: &mut Criterion) {
+    bench_day::<day01::ExpenseReport>(c, "day01", "day01/input.txt", "\n");
+    bench_day::<day02::PasswordDatabase>(c, "day02", "day02/input.txt", "\n");
+    bench_day::<day03::Map>(c, "day03", "day03/input.txt", "\n");
+    bench_day::<day04::Batch>(c, "day04", "day04/input.txt", "\n\n");
+    bench_day::<day05::BoardingPasses>(c, "day05", "day05/input.txt", "\n");
+    bench_day::<day06::Declarations>(c, "day06", "day06/input.txt", "\n\n");
+    bench_day::<day07::Rules>(c, "day07", "day07/input.txt", "\n");
+}
+
+/// Compares day01's search strategies on big expense reports with no answer, so every one of
+/// them has to search all the way through.
+fn day01_strategies(c: &mut Criterion) {
+    // even entries can never sum to an odd target
+    let entries = |n: usize| -> Vec<u16> {
+        let mut rng = StdRng::seed_from_u64(2020);
+        (0..n).map(|_| rng.gen_range(0..32768) * 2).collect()
+    };
+    let runs = [
+        (
+            2,
+            200_000,
+            &[
+                Strategy::Hash,
+                Strategy::TwoPointer,
+                Strategy::MeetInTheMiddle,
+            ][..],
+        ),
+        (2, 2_000, &[Strategy::Naive][..]),
+        (
+            3,
+            2_000,
+            &[
+                Strategy::Hash,
+                Strategy::TwoPointer,
+                Strategy::MeetInTheMiddle,
+            ][..],
+        ),
+    ];
+
+    let mut group = c.benchmark_group("day01_strategies");
+    group.sample_size(10);
+    for &(k, n, strategies) in runs.iter() {
+        let numbers = entries(n);
+        for &strategy in strategies {
+            let id = BenchmarkId::new(format!("{:?}", strategy), format!("k={} n={}", k, n));
+            group.bench_with_input(id, &numbers, |b, numbers| {
+                b.iter(|| day01::sum_k_with(numbers, k, 60001, strategy).is_ok())
+            });
+        }
+    }
+    group.finish();
+}
+
+/// Compares day02's hand-written line parser with the regex it replaced, on a generated file of
+/// a few million lines.
+fn day02_parsers(c: &mut Criterion) {
+    const LINES: usize = 2_000_000;
+    let input = PasswordDatabase::generate(&mut StdRng::seed_from_u64(2020), LINES).input;
+
+    let mut group = c.benchmark_group("day02_parsers");
+    group.sample_size(10);
+    group.throughput(Throughput::Bytes(input.len() as u64));
+    let id = |name| BenchmarkId::new(name, format!("{} lines", LINES));
+    group.bench_with_input(id("hand-written"), &input, |b, input| {
+        b.iter(|| {
+            input
+                .lines()
+                .filter(|line| PasswordEntry::parse(line).is_ok())
+                .count()
+        })
+    });
+    group.bench_with_input(id("regex"), &input, |b, input| {
+        b.iter(|| {
+            input
+                .lines()
+                .filter(|line| PasswordEntry::parse_regex(line).is_ok())
+                .count()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, days, day01_strategies, day02_parsers);
+criterion_main!(benches);
@@ -0,0 +1,80 @@
+use aoc::serde_json::Value;
+use aoc::{Error, Part, Report};
+
+/// A day registered with the runner.
+pub struct Day {
+    pub number: u8,
+    /// The committed puzzle input, relative to the workspace root.
+    pub input: &'static str,
+    /// The committed puzzle input itself, when built with the `embed-inputs` feature.
+    pub embedded: Option<&'static str>,
+    /// The known-good answers for each input, relative to the workspace root.
+    pub answers: &'static str,
+    pub solve: fn(&str, &[Part]) -> Result<Report, Error>,
+    /// Builds a random input of about the given size from a seed, with its expected answers.
+    pub generate: fn(u64, usize) -> (String, Value),
+}
+
+/// Every day in the workspace, in order. Add new days here.
+pub const DAYS: &[Day] = &[
+    Day {
+        number: 1,
+        input: "day01/input.txt",
+        embedded: day01::INPUT,
+        answers: "day01/answers.txt",
+        solve: aoc::solve::<day01::ExpenseReport>,
+        generate: aoc::generate::<day01::ExpenseReport>,
+    },
+    Day {
+        number: 2,
+        input: "day02/input.txt",
+        embedded: day02::INPUT,
+        answers: "day02/answers.txt",
+        solve: aoc::solve::<day02::PasswordDatabase>,
+        generate: aoc::generate::<day02::PasswordDatabase>,
+    },
+    Day {
+        number: 3,
+        input: "day03/input.txt",
+        embedded: day03::INPUT,
+        answers: "day03/answers.txt",
+        solve: aoc::solve::<day03::Map>,
+        generate: aoc::generate::<day03::Map>,
+    },
+    Day {
+        number: 4,
+        input: "day04/input.txt",
+        embedded: day04::INPUT,
+        answers: "day04/answers.txt",
+        solve: aoc::solve::<day04::Batch>,
+        generate: aoc::generate::<day04::Batch>,
+    },
+    Day {
+        number: 5,
+        input: "day05/input.txt",
+        embedded: day05::INPUT,
+        answers: "day05/answers.txt",
+        solve: aoc::solve::<day05::BoardingPasses>,
+        generate: aoc::generate::<day05::BoardingPasses>,
+    },
+    Day {
+        number: 6,
+        input: "day06/input.txt",
+        embedded: day06::INPUT,
+        answers: "day06/answers.txt",
+        solve: aoc::solve::<day06::Declarations>,
+        generate: aoc::generate::<day06::Declarations>,
+    },
+    Day {
+        number: 7,
+        input: "day07/input.txt",
+        embedded: day07::INPUT,
+        answers: "day07/answers.txt",
+        solve: aoc::solve::<day07::Rules>,
+        generate: aoc::generate::<day07::Rules>,
+    },
+];
+
+pub fn find(number: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|d| d.number == number)
+}
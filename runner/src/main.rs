@@ -0,0 +1,375 @@
+use aoc::answers::{self, Answers};
+use aoc::serde_json::json;
+use aoc::{Format, Part, Report, Source};
+use days::Day;
+use std::io::Write;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::{env, fs, io, process};
+
+mod days;
+
+const USAGE: &str = "usage:
+    aoc run <day|all> [--part <1|2>] [--input <path|->]... [--format <text|json>] [--verify] [--record]
+    aoc generate <day> [--size <n>] [--seed <n>]";
+
+enum Command {
+    Run(Args),
+    /// Print a random input for the day, with its expected answers on stderr.
+    Generate {
+        day: &'static Day,
+        size: usize,
+        seed: u64,
+    },
+}
+
+/// Which days to run, which parts of them, and (for a single day) where its inputs live.
+struct Args {
+    days: Vec<&'static Day>,
+    parts: Vec<Part>,
+    inputs: Vec<Source>,
+    format: Format,
+    /// Compare every answer against the day's answers file.
+    verify: bool,
+    /// Save every answer to the day's answers file.
+    record: bool,
+}
+
+fn parse_command(args: &[String]) -> Result<Command, String> {
+    let mut args = args.iter();
+
+    match args.next().map(String::as_str) {
+        Some("run") => parse_run(args).map(Command::Run),
+        Some("generate") => parse_generate(args),
+        Some(cmd) => Err(format!("unknown command: '{}'", cmd)),
+        None => Err(String::from("missing command")),
+    }
+}
+
+fn parse_day(day: Option<&String>) -> Result<&'static Day, String> {
+    let day = day.ok_or_else(|| String::from("missing day"))?;
+    let number = day
+        .parse::<u8>()
+        .map_err(|_| format!("invalid day: '{}'", day))?;
+    days::find(number).ok_or_else(|| format!("day {} isn't registered", number))
+}
+
+fn parse_generate<'a>(mut args: impl Iterator<Item = &'a String>) -> Result<Command, String> {
+    let day = parse_day(args.next())?;
+    let mut size = 1000;
+    // seeded from the clock unless asked otherwise; it's printed so a run can be repeated
+    let mut seed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos() as u64);
+
+    while let Some(flag) = args.next() {
+        let val = args
+            .next()
+            .ok_or_else(|| format!("missing value for '{}'", flag))?;
+        match flag.as_str() {
+            "--size" => {
+                size = val
+                    .parse()
+                    .map_err(|_| format!("invalid size: '{}'", val))?
+            }
+            "--seed" => {
+                seed = val
+                    .parse()
+                    .map_err(|_| format!("invalid seed: '{}'", val))?
+            }
+            _ => return Err(format!("unknown flag: '{}'", flag)),
+        }
+    }
+
+    Ok(Command::Generate { day, size, seed })
+}
+
+fn parse_run<'a>(mut args: impl Iterator<Item = &'a String>) -> Result<Args, String> {
+    let days = match args.next() {
+        Some(all) if all == "all" => days::DAYS.iter().collect(),
+        day => vec![parse_day(day)?],
+    };
+
+    let mut parts = vec![Part::One, Part::Two];
+    let mut inputs = Vec::new();
+    let mut format = Format::Text;
+    let mut verify = false;
+    let mut record = false;
+
+    while let Some(flag) = args.next() {
+        match flag.as_str() {
+            "--verify" => {
+                verify = true;
+                continue;
+            }
+            "--record" => {
+                record = true;
+                continue;
+            }
+            _ => {}
+        }
+
+        let val = args
+            .next()
+            .ok_or_else(|| format!("missing value for '{}'", flag))?;
+        match flag.as_str() {
+            "--part" => {
+                parts = match val.as_str() {
+                    "1" => vec![Part::One],
+                    "2" => vec![Part::Two],
+                    _ => return Err(format!("invalid part: '{}'", val)),
+                }
+            }
+            "--input" => inputs.push(Source::from_arg(val)),
+            "--format" => format = val.parse()?,
+            _ => return Err(format!("unknown flag: '{}'", flag)),
+        }
+    }
+
+    if !inputs.is_empty() && days.len() != 1 {
+        return Err(String::from(
+            "--input can only be used when running a single day",
+        ));
+    }
+
+    if verify && record {
+        return Err(String::from("--verify and --record can't be used together"));
+    }
+
+    Ok(Args {
+        days,
+        parts,
+        inputs,
+        format,
+        verify,
+        record,
+    })
+}
+
+fn workspace_root() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap()
+}
+
+/// The input compiled into the day if there is one, otherwise its committed input file.
+fn default_input(day: &Day) -> Source {
+    match day.embedded {
+        Some(input) => Source::Embedded(input),
+        None => Source::File(workspace_root().join(day.input)),
+    }
+}
+
+fn display_name(source: &Source) -> String {
+    match source {
+        Source::File(path) => path
+            .strip_prefix(workspace_root())
+            .unwrap_or(path)
+            .to_string_lossy()
+            .into_owned(),
+        source => source.name(),
+    }
+}
+
+fn print_row(
+    out: &mut impl Write,
+    day: &str,
+    part: &str,
+    answer: &str,
+    elapsed: &str,
+    input: &str,
+) -> io::Result<()> {
+    let row = format!(
+        "{:<5} {:<6} {:>16} {:>12}  {}",
+        day, part, answer, elapsed, input
+    );
+    writeln!(out, "{}", row.trim_end())
+}
+
+fn print_rows(out: &mut impl Write, name: &str, input: &str, report: &Report) -> io::Result<()> {
+    let parse = format!("{:.1?}", report.parse);
+    print_row(out, name, "parse", "", &parse, input)?;
+    for (part, answer) in report.parts.iter() {
+        print_row(
+            out,
+            name,
+            &part.to_string(),
+            &report.answer(*part).unwrap_or_default(),
+            &format!("{:.1?}", answer.elapsed),
+            input,
+        )?;
+    }
+    Ok(())
+}
+
+/// Solves the input, returning its checksum alongside the report.
+fn solve(day: &Day, source: &Source, parts: &[Part]) -> Result<(u64, Report), aoc::Error> {
+    let input = source.read()?;
+    let report = (day.solve)(&input, parts).map_err(|e| e.in_file(&source.name()))?;
+    Ok((answers::checksum(&input), report))
+}
+
+/// Loads the day's answers file, treating a missing file as having no answers yet.
+fn load_answers(day: &Day) -> Result<Answers, aoc::Error> {
+    let path = workspace_root().join(day.answers);
+    match fs::read_to_string(&path) {
+        Ok(text) => Answers::parse(&text).map_err(|e| e.in_file(day.answers)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Answers::default()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Compares a run against the recorded answers, printing a diff for every answer that changed.
+/// Returns whether everything matched.
+fn verify(name: &str, input: &str, checksum: u64, report: &Report, answers: &Answers) -> bool {
+    let recorded = match answers.get(checksum) {
+        Some(recorded) => recorded,
+        None => {
+            eprintln!(
+                "day {}: no recorded answers for {} ({:016x})",
+                name, input, checksum
+            );
+            return false;
+        }
+    };
+
+    let mut ok = true;
+    for (part, _) in report.parts.iter() {
+        let expected = recorded.get(*part);
+        let actual = report.answer(*part).unwrap_or_default();
+        if expected != actual {
+            eprintln!("day {} part {} changed on {}:", name, part, input);
+            eprintln!("  - {}", expected);
+            eprintln!("  + {}", actual);
+            ok = false;
+        }
+    }
+    ok
+}
+
+/// Runs every requested day against each of its inputs, reporting the ones that fail without
+/// stopping the rest. Returns whether every run succeeded (and, when verifying, matched).
+fn run(args: Args) -> bool {
+    let mut ok = true;
+    let written = run_to(&mut io::stdout().lock(), args).map(|all_ok| ok = all_ok);
+    match aoc::ignore_broken_pipe(written) {
+        Ok(()) => ok,
+        Err(e) => {
+            eprintln!("failed to write output: {}", e);
+            false
+        }
+    }
+}
+
+fn run_to(out: &mut impl Write, args: Args) -> io::Result<bool> {
+    let text = args.format == Format::Text;
+    if text {
+        print_row(out, "day", "part", "answer", "time", "input")?;
+    }
+
+    let mut ok = true;
+    let mut total = Duration::default();
+    for day in args.days {
+        let defaults;
+        let sources = if args.inputs.is_empty() {
+            defaults = [default_input(day)];
+            &defaults[..]
+        } else {
+            &args.inputs[..]
+        };
+        let name = format!("{:02}", day.number);
+
+        let mut answers = if args.verify || args.record {
+            match load_answers(day) {
+                Ok(answers) => answers,
+                Err(e) => {
+                    eprintln!("day {} failed:\n{}", name, e);
+                    ok = false;
+                    continue;
+                }
+            }
+        } else {
+            Answers::default()
+        };
+
+        for source in sources {
+            let input = display_name(source);
+            let (checksum, report) = match solve(day, source, &args.parts) {
+                Ok(solved) => solved,
+                Err(e) => {
+                    eprintln!("day {} failed on {}:\n{}", name, input, e);
+                    if !text {
+                        let doc =
+                            json!({ "day": day.number, "input": input, "error": e.to_string() });
+                        writeln!(out, "{}", doc)?;
+                    }
+                    ok = false;
+                    continue;
+                }
+            };
+
+            total += report.parse;
+            for (_, answer) in report.parts.iter() {
+                total += answer.elapsed;
+            }
+
+            if text {
+                print_rows(out, &name, &input, &report)?;
+            } else {
+                let mut doc = report.to_json();
+                doc["day"] = json!(day.number);
+                doc["input"] = json!(input);
+                doc["checksum"] = json!(format!("{:016x}", checksum));
+                writeln!(out, "{}", doc)?;
+            }
+
+            if args.verify {
+                ok &= verify(&name, &input, checksum, &report, &answers);
+            }
+
+            if args.record {
+                for (part, _) in report.parts.iter() {
+                    answers.record(checksum, *part, report.answer(*part).unwrap_or_default());
+                }
+            }
+        }
+
+        if args.record {
+            if let Err(e) = fs::write(workspace_root().join(day.answers), answers.to_string()) {
+                eprintln!("day {}: failed to write {}: {}", name, day.answers, e);
+                ok = false;
+            }
+        }
+    }
+
+    if text {
+        print_row(out, "total", "", "", &format!("{:.1?}", total), "")?;
+    }
+
+    Ok(ok)
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let command = parse_command(&args).unwrap_or_else(|e| {
+        eprintln!("{}\n{}", e, USAGE);
+        process::exit(2);
+    });
+
+    match command {
+        Command::Run(args) => {
+            if !run(args) {
+                process::exit(1);
+            }
+        }
+        Command::Generate { day, size, seed } => {
+            let (input, answers) = (day.generate)(seed, size);
+            let written = io::stdout().lock().write_all(input.as_bytes());
+            if let Err(e) = aoc::ignore_broken_pipe(written) {
+                eprintln!("failed to write output: {}", e);
+                process::exit(1);
+            }
+            eprintln!("seed: {}", seed);
+            eprintln!("answers: {}", answers);
+        }
+    }
+}